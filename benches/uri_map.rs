@@ -0,0 +1,62 @@
+//! Compares lookups in maps keyed by `Uri` and by `CachedUri`.
+//!
+//! Run with `cargo bench --bench uri_map`.
+
+use std::{
+    collections::HashMap,
+    hint::black_box,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use ls_types::{BuildCachedUriHasher, CachedUri, Uri};
+
+const DOCUMENTS: usize = 10_000;
+const ROUNDS: usize = 20;
+
+fn uris() -> Vec<Uri> {
+    (0..DOCUMENTS)
+        .map(|i| {
+            Uri::from_str(&format!(
+                "file:///home/user/projects/workspace/crates/crate-{}/src/module_{i}.rs",
+                i % 100
+            ))
+            .unwrap()
+        })
+        .collect()
+}
+
+fn measure(name: &str, mut lookups: impl FnMut() -> usize) {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        black_box(lookups());
+        best = best.min(start.elapsed());
+    }
+    println!("{name:<24} {best:>10.1?} per {DOCUMENTS} lookups (best of {ROUNDS})");
+}
+
+fn main() {
+    let uris = uris();
+
+    let plain: HashMap<Uri, usize> = uris.iter().cloned().zip(0..).collect();
+    measure("HashMap<Uri, _>", || {
+        uris.iter().filter(|uri| plain.contains_key(*uri)).count()
+    });
+
+    let cached_uris: Vec<CachedUri> = uris.iter().cloned().map(CachedUri::new).collect();
+    let cached: HashMap<CachedUri, usize, BuildCachedUriHasher> =
+        cached_uris.iter().cloned().zip(0..).collect();
+    measure("HashMap<CachedUri, _>", || {
+        cached_uris
+            .iter()
+            .filter(|uri| cached.contains_key(*uri))
+            .count()
+    });
+
+    measure("  incl. key computation", || {
+        uris.iter()
+            .filter(|uri| cached.contains_key(&CachedUri::new((*uri).clone())))
+            .count()
+    });
+}
@@ -296,6 +296,18 @@ pub struct CodeAction {
     pub data: Option<Value>,
 }
 
+impl CodeAction {
+    /// Marks this code action as disabled, displaying `reason` in the client UI.
+    ///
+    /// Only send disabled actions to clients declaring
+    /// `textDocument.codeAction.disabledSupport`.
+    #[must_use]
+    pub fn with_disabled(mut self, reason: String) -> Self {
+        self.disabled = Some(CodeActionDisabled::new(reason));
+        self
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionDisabled {
@@ -305,6 +317,13 @@ pub struct CodeActionDisabled {
     pub reason: String,
 }
 
+impl CodeActionDisabled {
+    #[must_use]
+    pub const fn new(reason: String) -> Self {
+        Self { reason }
+    }
+}
+
 /// The reason why code actions were requested.
 ///
 /// @since 3.17.0
@@ -347,6 +366,20 @@ pub struct CodeActionContext {
     pub trigger_kind: Option<CodeActionTriggerKind>,
 }
 
+impl CodeActionContext {
+    /// Returns the first diagnostic sent back by the client whose range is
+    /// exactly `range`.
+    ///
+    /// Clients echo diagnostics unchanged, including their `data`, so the
+    /// returned diagnostic can be matched against the one originally published.
+    #[must_use]
+    pub fn diagnostic_for(&self, range: &Range) -> Option<&Diagnostic> {
+        self.diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.range == *range)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionOptions {
@@ -368,10 +401,28 @@ pub struct CodeActionOptions {
     pub resolve_provider: Option<bool>,
 }
 
+impl CodeActionOptions {
+    /// Sets the `CodeActionKinds` that this server may return.
+    #[must_use]
+    pub fn with_code_action_kinds(mut self, code_action_kinds: Vec<CodeActionKind>) -> Self {
+        self.code_action_kinds = Some(code_action_kinds);
+        self
+    }
+
+    /// Sets whether the server supports `codeAction/resolve`.
+    #[must_use]
+    pub const fn with_resolve_provider(mut self, resolve_provider: bool) -> Self {
+        self.resolve_provider = Some(resolve_provider);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tests::test_serialization;
+    use crate::{Position, ResolveData};
+    use serde_json::json;
 
     #[test]
     fn test_code_action_response() {
@@ -395,4 +446,57 @@ mod tests {
             r#"[{"title":"title","command":"command"},{"title":"title","kind":"quickfix"}]"#,
         );
     }
+
+    #[test]
+    fn test_disabled_code_action() {
+        test_serialization(
+            &CodeAction {
+                title: "Extract method".to_string(),
+                kind: Some(CodeActionKind::REFACTOR_EXTRACT),
+                ..CodeAction::default()
+            }
+            .with_disabled("Selection is not an expression".to_string()),
+            r#"{"title":"Extract method","kind":"refactor.extract","disabled":{"reason":"Selection is not an expression"}}"#,
+        );
+    }
+
+    #[test]
+    fn test_code_action_options_builder() {
+        test_serialization(
+            &CodeActionOptions::default()
+                .with_code_action_kinds(vec![CodeActionKind::QUICKFIX])
+                .with_resolve_provider(true),
+            r#"{"codeActionKinds":["quickfix"],"resolveProvider":true}"#,
+        );
+    }
+
+    #[test]
+    fn test_code_action_context_diagnostic_for() {
+        let context: CodeActionContext = serde_json::from_str(
+            r#"{"diagnostics":[{"range":{"start":{"line":1,"character":0},"end":{"line":1,"character":4}},"message":"unused","data":{"fix":"remove","id":7}}],"triggerKind":1}"#,
+        )
+        .unwrap();
+
+        let range = Range::new(Position::new(1, 0), Position::new(1, 4));
+        let diagnostic = context.diagnostic_for(&range).unwrap();
+        assert_eq!(diagnostic.message, "unused");
+        assert_eq!(diagnostic.data, Some(json!({"fix": "remove", "id": 7})));
+
+        let other = Range::new(Position::new(1, 0), Position::new(1, 5));
+        assert_eq!(context.diagnostic_for(&other), None);
+    }
+
+    #[test]
+    fn code_action_resolve_data() {
+        let mut action = CodeAction::default();
+        assert!(action.data_as::<u32>().is_none());
+
+        action.set_data(&("fix", 3)).unwrap();
+        assert_eq!(action.data, Some(json!(["fix", 3])));
+        assert_eq!(
+            action.data_as::<(String, u32)>().unwrap().unwrap(),
+            ("fix".to_string(), 3)
+        );
+        assert!(action.data_as::<bool>().unwrap().is_err());
+    }
 }
@@ -74,3 +74,27 @@ pub struct CodeLensRegistrationOptions {
     #[serde(flatten)]
     pub code_lens_options: CodeLensOptions,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Position, ResolveData};
+    use serde_json::json;
+
+    #[test]
+    fn code_lens_resolve_data() {
+        let mut lens = CodeLens {
+            range: Range::new(Position::new(0, 0), Position::new(0, 3)),
+            command: None,
+            data: None,
+        };
+        assert!(lens.data_as::<String>().is_none());
+
+        lens.set_data(&"tests::it_works").unwrap();
+        assert_eq!(lens.data, Some(json!("tests::it_works")));
+        assert_eq!(
+            lens.data_as::<String>().unwrap().unwrap(),
+            "tests::it_works"
+        );
+    }
+}
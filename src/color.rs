@@ -1,6 +1,6 @@
 use crate::{
     DocumentSelector, DynamicRegistrationClientCapabilities, PartialResultParams, Range,
-    TextDocumentIdentifier, TextEdit, WorkDoneProgressParams,
+    TextDocumentIdentifier, TextEdit, Uri, WorkDoneProgressParams,
 };
 use serde::{Deserialize, Serialize};
 
@@ -60,6 +60,18 @@ pub struct DocumentColorParams {
     pub partial_result_params: PartialResultParams,
 }
 
+impl DocumentColorParams {
+    /// Creates params asking for the colors in the document at `uri`.
+    #[must_use]
+    pub fn new(uri: Uri) -> Self {
+        Self {
+            text_document: TextDocumentIdentifier::new(uri),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ColorInformation {
@@ -69,6 +81,13 @@ pub struct ColorInformation {
     pub color: Color,
 }
 
+impl ColorInformation {
+    #[must_use]
+    pub const fn new(range: Range, color: Color) -> Self {
+        Self { range, color }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Copy)]
 #[serde(rename_all = "camelCase")]
 pub struct Color {
@@ -82,6 +101,18 @@ pub struct Color {
     pub alpha: f32,
 }
 
+impl Color {
+    #[must_use]
+    pub const fn new(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ColorPresentationParams {
@@ -101,6 +132,21 @@ pub struct ColorPresentationParams {
     pub partial_result_params: PartialResultParams,
 }
 
+impl ColorPresentationParams {
+    /// Creates params asking for the presentations of `color` at `range` in
+    /// the document at `uri`.
+    #[must_use]
+    pub fn new(uri: Uri, color: Color, range: Range) -> Self {
+        Self {
+            text_document: TextDocumentIdentifier::new(uri),
+            color,
+            range,
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ColorPresentation {
@@ -120,3 +166,57 @@ pub struct ColorPresentation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub additional_text_edits: Option<Vec<TextEdit>>,
 }
+
+impl ColorPresentation {
+    /// Creates a presentation inserting `label`.
+    #[must_use]
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Position,
+        request::{ColorPresentationRequest, DocumentColor, Request},
+        tests::test_serialization,
+    };
+
+    #[test]
+    fn document_color_request() {
+        assert_eq!(DocumentColor::METHOD, "textDocument/documentColor");
+        test_serialization(
+            &DocumentColorParams::new("file:///a.css".parse().unwrap()),
+            r#"{"textDocument":{"uri":"file:///a.css"}}"#,
+        );
+        test_serialization(
+            &ColorInformation::new(
+                Range::new(Position::new(0, 7), Position::new(0, 11)),
+                Color::new(1.0, 0.0, 0.0, 1.0),
+            ),
+            r#"{"range":{"start":{"line":0,"character":7},"end":{"line":0,"character":11}},"color":{"red":1.0,"green":0.0,"blue":0.0,"alpha":1.0}}"#,
+        );
+    }
+
+    #[test]
+    fn color_presentation_request() {
+        assert_eq!(
+            ColorPresentationRequest::METHOD,
+            "textDocument/colorPresentation"
+        );
+        test_serialization(
+            &ColorPresentationParams::new(
+                "file:///a.css".parse().unwrap(),
+                Color::new(0.0, 0.0, 1.0, 0.5),
+                Range::new(Position::new(0, 7), Position::new(0, 11)),
+            ),
+            r#"{"textDocument":{"uri":"file:///a.css"},"color":{"red":0.0,"green":0.0,"blue":1.0,"alpha":0.5},"range":{"start":{"line":0,"character":7},"end":{"line":0,"character":11}}}"#,
+        );
+        test_serialization(&ColorPresentation::new("blue"), r#"{"label":"blue"}"#);
+    }
+}
@@ -2,13 +2,14 @@ use serde::{Deserialize, Serialize};
 
 use crate::macros::lsp_enum;
 use crate::{
-    Command, Documentation, MarkupKind, PartialResultParams, TagSupport,
-    TextDocumentPositionParams, TextDocumentRegistrationOptions, TextEdit, WorkDoneProgressOptions,
-    WorkDoneProgressParams,
+    Command, Documentation, EditError, MarkupKind, PartialResultParams, Position, TagSupport,
+    TextDocumentIdentifier, TextDocumentPositionParams, TextDocumentRegistrationOptions, TextEdit,
+    Uri, WorkDoneProgressOptions, WorkDoneProgressParams,
 };
 
-use crate::Range;
+use crate::{Range, check_overlap};
 use serde_json::Value;
+use std::collections::HashSet;
 use std::fmt::Debug;
 
 /// Defines how to interpret the insert text in a completion item
@@ -23,8 +24,23 @@ lsp_enum! {
     }
 }
 
+/// Escapes `$`, `}` and `\\` in `text` so that it is inserted literally when
+/// embedded in an [`InsertTextFormat::SNIPPET`] value, e.g. an identifier
+/// like `$scope` that would otherwise be read as a variable.
+#[must_use]
+pub fn escape_snippet(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '$' | '}' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 /// The kind of a completion entry.
-#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct CompletionItemKind(i32);
 
@@ -382,6 +398,31 @@ pub struct CompletionParams {
     pub context: Option<CompletionContext>,
 }
 
+impl CompletionParams {
+    /// Creates completion params without a `CompletionContext`.
+    #[must_use]
+    pub fn new(uri: Uri, position: Position) -> Self {
+        Self {
+            text_document_position: TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(uri),
+                position,
+            ),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+            context: None,
+        }
+    }
+
+    /// Creates completion params describing how completion was triggered.
+    #[must_use]
+    pub fn with_context(uri: Uri, position: Position, context: CompletionContext) -> Self {
+        Self {
+            context: Some(context),
+            ..Self::new(uri, position)
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionContext {
@@ -416,10 +457,141 @@ pub struct CompletionList {
     /// this list.
     pub is_incomplete: bool,
 
+    /// Default values for properties of the items in this list. Items that
+    /// provide a property themselves override the default.
+    ///
+    /// Servers are only allowed to return default values if the client
+    /// signals support for them via the `completionList.itemDefaults`
+    /// capability.
+    ///
+    /// @since 3.17.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_defaults: Option<CompletionItemDefaults>,
+
     /// The completion items.
     pub items: Vec<CompletionItem>,
 }
 
+/// Default values for the properties of the items of a [`CompletionList`].
+///
+/// @since 3.17.0
+#[derive(Debug, PartialEq, Eq, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionItemDefaults {
+    /// A default commit character set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_characters: Option<Vec<String>>,
+
+    /// A default edit range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit_range: Option<CompletionItemDefaultsEditRange>,
+
+    /// A default insert text format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insert_text_format: Option<InsertTextFormat>,
+
+    /// A default insert text mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insert_text_mode: Option<InsertTextMode>,
+
+    /// A default data value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+/// The default edit range of a [`CompletionItemDefaults`]: either a single
+/// range or an insert and replace range pair.
+///
+/// @since 3.17.0
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum CompletionItemDefaultsEditRange {
+    Range(Range),
+    InsertAndReplace { insert: Range, replace: Range },
+}
+
+impl CompletionList {
+    /// Merges `other` into this list, e.g. when completions are re-requested
+    /// for an incomplete list.
+    ///
+    /// Items of `other` are appended unless an item with the same `label` and
+    /// `kind` is already present. The result is incomplete if either list is.
+    ///
+    /// If the `item_defaults` of both lists differ, both are expanded with
+    /// [`CompletionList::expand_defaults`] first, so that every item keeps
+    /// the defaults of the list it came from.
+    pub fn merge(&mut self, mut other: CompletionList) {
+        if self.item_defaults != other.item_defaults {
+            self.expand_defaults();
+            other.expand_defaults();
+        }
+        self.is_incomplete |= other.is_incomplete;
+
+        let mut seen: HashSet<(String, Option<CompletionItemKind>)> = self
+            .items
+            .iter()
+            .map(|item| (item.label.clone(), item.kind))
+            .collect();
+        for item in other.items {
+            if seen.insert((item.label.clone(), item.kind)) {
+                self.items.push(item);
+            }
+        }
+    }
+
+    /// Applies the `item_defaults` of this list to every item lacking the
+    /// corresponding property, and clears them, for clients that do not
+    /// support `completionList.itemDefaults`.
+    ///
+    /// A default edit range becomes a `text_edit` whose new text is the
+    /// item's `insert_text`, or its `label` if unset, and `insert_text` is
+    /// then cleared since clients ignore it next to a `text_edit`. The
+    /// protocol takes the new text from `textEditText`, which this crate does
+    /// not model, so `insert_text` stands in for it.
+    pub fn expand_defaults(&mut self) {
+        let Some(defaults) = self.item_defaults.take() else {
+            return;
+        };
+
+        for item in &mut self.items {
+            if item.commit_characters.is_none() {
+                item.commit_characters
+                    .clone_from(&defaults.commit_characters);
+            }
+            if item.insert_text_format.is_none() {
+                item.insert_text_format = defaults.insert_text_format;
+            }
+            if item.insert_text_mode.is_none() {
+                item.insert_text_mode = defaults.insert_text_mode;
+            }
+            if item.data.is_none() {
+                item.data.clone_from(&defaults.data);
+            }
+            if item.text_edit.is_none()
+                && let Some(edit_range) = &defaults.edit_range
+            {
+                let new_text = item
+                    .insert_text
+                    .take()
+                    .unwrap_or_else(|| item.label.clone());
+                item.text_edit = Some(match *edit_range {
+                    CompletionItemDefaultsEditRange::Range(range) => {
+                        TextEdit::new(range, new_text).into()
+                    }
+                    CompletionItemDefaultsEditRange::InsertAndReplace { insert, replace } => {
+                        InsertReplaceEdit {
+                            new_text,
+                            insert,
+                            replace,
+                        }
+                        .into()
+                    }
+                });
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Default, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionItem {
@@ -557,6 +729,63 @@ impl CompletionItem {
             ..Self::default()
         }
     }
+
+    /// Create a `CompletionItem` inserting `snippet`, interpreted as a snippet
+    /// rather than plain text. See [`escape_snippet`] for literal parts.
+    #[must_use]
+    pub fn snippet(label: String, snippet: String) -> Self {
+        Self {
+            label,
+            insert_text: Some(snippet),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Self::default()
+        }
+    }
+
+    /// Sets whether this item is selected when the completion list is shown.
+    #[must_use]
+    pub const fn with_preselect(mut self, preselect: bool) -> Self {
+        self.preselect = Some(preselect);
+        self
+    }
+
+    /// Sets the characters that accept this item when typed while it is active.
+    #[must_use]
+    pub fn with_commit_characters(mut self, commit_characters: Vec<String>) -> Self {
+        self.commit_characters = Some(commit_characters);
+        self
+    }
+
+    /// Clears `insert_text` if a `text_edit` is set, since clients ignore the
+    /// former in that case.
+    ///
+    /// Returns whether `insert_text` was cleared, i.e. whether the item was
+    /// contradictory, so servers can report it.
+    pub fn normalize(&mut self) -> bool {
+        self.text_edit.is_some() && self.insert_text.take().is_some()
+    }
+
+    /// Checks that the `additional_text_edits` overlap neither each other nor
+    /// the main `text_edit`, as required by the protocol.
+    ///
+    /// For an `InsertReplaceEdit`, the replace range is checked since it
+    /// contains the insert range.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first pair of overlapping ranges found.
+    pub fn validate_edits(&self) -> Result<(), EditError> {
+        let main = self.text_edit.as_ref().map(|edit| match edit {
+            CompletionTextEdit::Edit(edit) => edit.range,
+            CompletionTextEdit::InsertAndReplace(edit) => edit.replace,
+        });
+        let additional = self
+            .additional_text_edits
+            .iter()
+            .flatten()
+            .map(|edit| edit.range);
+        check_overlap(main.into_iter().chain(additional))
+    }
 }
 
 /// Additional details for a completion item label.
@@ -581,7 +810,10 @@ pub struct CompletionItemLabelDetails {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::test_deserialization;
+    use crate::{
+        ResolveData,
+        tests::{test_deserialization, test_serialization},
+    };
 
     #[test]
     fn test_tag_support_deserialization() {
@@ -607,6 +839,235 @@ mod tests {
         test_deserialization(r#"{"tagSupport": {"valueSet": [1]}}"#, &t);
     }
 
+    #[test]
+    fn test_completion_params_constructors() {
+        let uri: Uri = "file:///a.rs".parse().unwrap();
+        test_serialization(
+            &CompletionParams::new(uri.clone(), Position::new(1, 2)),
+            r#"{"textDocument":{"uri":"file:///a.rs"},"position":{"line":1,"character":2}}"#,
+        );
+
+        test_serialization(
+            &CompletionParams::with_context(
+                uri,
+                Position::new(1, 2),
+                CompletionContext {
+                    trigger_kind: CompletionTriggerKind::TRIGGER_CHARACTER,
+                    trigger_character: Some(".".into()),
+                },
+            ),
+            r#"{"textDocument":{"uri":"file:///a.rs"},"position":{"line":1,"character":2},"context":{"triggerKind":2,"triggerCharacter":"."}}"#,
+        );
+    }
+
+    #[test]
+    fn test_completion_item_builder() {
+        test_serialization(
+            &CompletionItem::new_simple("foo".into(), "fn()".into())
+                .with_preselect(true)
+                .with_commit_characters(vec![".".into(), "(".into()]),
+            r#"{"label":"foo","detail":"fn()","preselect":true,"commitCharacters":[".","("]}"#,
+        );
+    }
+
+    #[test]
+    fn test_completion_item_normalize() {
+        let edit = TextEdit::new(
+            Range::new(Position::new(0, 0), Position::new(0, 2)),
+            "foo".into(),
+        );
+        let mut item = CompletionItem {
+            label: "foo".into(),
+            insert_text: Some("foo()".into()),
+            text_edit: Some(edit.clone().into()),
+            ..Default::default()
+        };
+        assert!(item.normalize());
+        assert_eq!(item.insert_text, None);
+        assert_eq!(item.text_edit, Some(edit.into()));
+        assert!(!item.normalize());
+
+        let mut item = CompletionItem {
+            label: "foo".into(),
+            insert_text: Some("foo()".into()),
+            ..Default::default()
+        };
+        assert!(!item.normalize());
+        assert_eq!(item.insert_text.as_deref(), Some("foo()"));
+    }
+
+    #[test]
+    fn test_completion_item_validate_edits() {
+        let range = |start, end| Range::new(Position::new(0, start), Position::new(0, end));
+        let mut item = CompletionItem {
+            label: "foo".into(),
+            text_edit: Some(CompletionTextEdit::InsertAndReplace(InsertReplaceEdit {
+                new_text: "foo".into(),
+                insert: range(4, 6),
+                replace: range(4, 8),
+            })),
+            additional_text_edits: Some(vec![
+                TextEdit::new(range(0, 0), "use foo;".into()),
+                TextEdit::new(range(8, 9), String::new()),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(item.validate_edits(), Ok(()));
+
+        item.additional_text_edits
+            .as_mut()
+            .unwrap()
+            .push(TextEdit::new(range(7, 7), "x".into()));
+        assert_eq!(
+            item.validate_edits(),
+            Err(EditError::Overlap {
+                first: range(4, 8),
+                second: range(7, 7),
+            })
+        );
+
+        item.text_edit = None;
+        item.additional_text_edits = Some(vec![
+            TextEdit::new(range(0, 3), String::new()),
+            TextEdit::new(range(2, 5), String::new()),
+        ]);
+        assert_eq!(
+            item.validate_edits(),
+            Err(EditError::Overlap {
+                first: range(0, 3),
+                second: range(2, 5),
+            })
+        );
+    }
+
+    #[test]
+    fn test_completion_list_merge() {
+        let item = |label: &str, kind| CompletionItem {
+            label: label.into(),
+            kind: Some(kind),
+            ..Default::default()
+        };
+        let mut list = CompletionList {
+            is_incomplete: false,
+            items: vec![item("foo", CompletionItemKind::FUNCTION)],
+            ..Default::default()
+        };
+        list.merge(CompletionList {
+            is_incomplete: true,
+            items: vec![
+                item("foo", CompletionItemKind::FUNCTION),
+                item("foo", CompletionItemKind::FIELD),
+                item("bar", CompletionItemKind::FUNCTION),
+                item("bar", CompletionItemKind::FUNCTION),
+            ],
+            ..Default::default()
+        });
+
+        assert!(list.is_incomplete);
+        assert_eq!(
+            list.items,
+            vec![
+                item("foo", CompletionItemKind::FUNCTION),
+                item("foo", CompletionItemKind::FIELD),
+                item("bar", CompletionItemKind::FUNCTION),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_completion_list_merge_defaults() {
+        let defaults = |data: i32| {
+            Some(CompletionItemDefaults {
+                data: Some(data.into()),
+                ..Default::default()
+            })
+        };
+        let list = |label: &str, item_defaults| CompletionList {
+            is_incomplete: true,
+            item_defaults,
+            items: vec![CompletionItem::new_simple(label.into(), String::new())],
+        };
+        let data = |list: &CompletionList| {
+            list.items
+                .iter()
+                .map(|item| item.data.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut merged = list("foo", defaults(1));
+        merged.merge(list("bar", defaults(1)));
+        assert_eq!(merged.item_defaults, defaults(1));
+        assert_eq!(data(&merged), [None, None]);
+
+        let mut merged = list("foo", defaults(1));
+        merged.merge(list("bar", defaults(2)));
+        assert_eq!(merged.item_defaults, None);
+        assert_eq!(data(&merged), [Some(1.into()), Some(2.into())]);
+
+        let mut merged = list("foo", None);
+        merged.merge(list("bar", defaults(2)));
+        assert_eq!(merged.item_defaults, None);
+        assert_eq!(data(&merged), [None, Some(2.into())]);
+    }
+
+    #[test]
+    fn test_completion_list_expand_defaults() {
+        let range = |start, end| Range::new(Position::new(0, start), Position::new(0, end));
+        let mut list: CompletionList = serde_json::from_str(
+            r#"{
+                "isIncomplete": false,
+                "itemDefaults": {
+                    "commitCharacters": ["."],
+                    "editRange": {
+                        "insert": {"start": {"line": 0, "character": 4}, "end": {"line": 0, "character": 6}},
+                        "replace": {"start": {"line": 0, "character": 4}, "end": {"line": 0, "character": 8}}
+                    },
+                    "insertTextFormat": 2,
+                    "data": 1
+                },
+                "items": [
+                    {"label": "foo"},
+                    {"label": "bar", "insertText": "bar()", "commitCharacters": [], "data": 2}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        list.expand_defaults();
+
+        assert_eq!(list.item_defaults, None);
+        assert!(!list.items.iter_mut().any(CompletionItem::normalize));
+        assert_eq!(
+            list.items,
+            vec![
+                CompletionItem {
+                    label: "foo".into(),
+                    commit_characters: Some(vec![".".into()]),
+                    insert_text_format: Some(InsertTextFormat::SNIPPET),
+                    text_edit: Some(CompletionTextEdit::InsertAndReplace(InsertReplaceEdit {
+                        new_text: "foo".into(),
+                        insert: range(4, 6),
+                        replace: range(4, 8),
+                    })),
+                    data: Some(1.into()),
+                    ..Default::default()
+                },
+                CompletionItem {
+                    label: "bar".into(),
+                    commit_characters: Some(vec![]),
+                    insert_text_format: Some(InsertTextFormat::SNIPPET),
+                    text_edit: Some(CompletionTextEdit::InsertAndReplace(InsertReplaceEdit {
+                        new_text: "bar()".into(),
+                        insert: range(4, 6),
+                        replace: range(4, 8),
+                    })),
+                    data: Some(2.into()),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_debug_enum() {
         assert_eq!(format!("{:?}", CompletionItemKind::TEXT), "Text");
@@ -616,6 +1077,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_display_enum() {
+        assert_eq!(CompletionItemKind::TEXT.to_string(), "TEXT");
+        assert_eq!(
+            CompletionItemKind::TYPE_PARAMETER.to_string(),
+            "TYPE_PARAMETER"
+        );
+        assert_eq!(crate::SymbolKind::CLASS.to_string(), "CLASS");
+        assert_eq!(CompletionItemKind(99).to_string(), "Unknown(99)");
+    }
+
     #[test]
     fn test_try_from_enum() {
         use std::convert::TryInto;
@@ -625,4 +1097,69 @@ mod tests {
             Ok(CompletionItemKind::TYPE_PARAMETER)
         );
     }
+
+    #[test]
+    fn completion_item_resolve_data() {
+        #[derive(Debug, PartialEq, Deserialize, Serialize)]
+        struct Data {
+            symbol_id: u64,
+        }
+
+        let mut item = CompletionItem::new_simple("foo".to_string(), String::new());
+        item.set_data(&Data { symbol_id: 42 }).unwrap();
+        test_serialization(
+            &item,
+            r#"{"label":"foo","detail":"","data":{"symbol_id":42}}"#,
+        );
+        assert_eq!(
+            item.data_as::<Data>().unwrap().unwrap(),
+            Data { symbol_id: 42 }
+        );
+    }
+
+    #[test]
+    fn test_insert_text_mode() {
+        let item = CompletionItem {
+            label: "if".to_string(),
+            insert_text: Some("if $1 {\n\t$0\n}".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            insert_text_mode: Some(InsertTextMode::ADJUST_INDENTATION),
+            ..Default::default()
+        };
+        test_serialization(
+            &item,
+            r#"{"label":"if","insertText":"if $1 {\n\t$0\n}","insertTextFormat":2,"insertTextMode":2}"#,
+        );
+
+        let list = CompletionList {
+            is_incomplete: false,
+            item_defaults: Some(CompletionItemDefaults {
+                insert_text_mode: Some(InsertTextMode::AS_IS),
+                ..Default::default()
+            }),
+            items: vec![],
+        };
+        test_serialization(
+            &list,
+            r#"{"isIncomplete":false,"itemDefaults":{"insertTextMode":1},"items":[]}"#,
+        );
+    }
+
+    #[test]
+    fn test_escape_snippet() {
+        assert_eq!(escape_snippet("$scope"), "\\$scope");
+        assert_eq!(escape_snippet("a\\b}c"), "a\\\\b\\}c");
+        assert_eq!(escape_snippet("plain_name"), "plain_name");
+
+        let snippet = escape_snippet("$set") + "($1)$0";
+        assert_eq!(snippet, "\\$set($1)$0");
+    }
+
+    #[test]
+    fn test_snippet_item() {
+        test_serialization(
+            &CompletionItem::snippet("println!".to_string(), "println!(\"$1\")$0".to_string()),
+            r#"{"label":"println!","insertText":"println!(\"$1\")$0","insertTextFormat":2}"#,
+        );
+    }
 }
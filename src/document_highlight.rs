@@ -48,3 +48,64 @@ lsp_enum! {
         const WRITE = 3;
     }
 }
+
+/// Merges the highlights of the same kind that overlap or touch into a single
+/// highlight spanning them, since some clients render overlapping highlights
+/// oddly.
+///
+/// A missing kind is treated as [`DocumentHighlightKind::TEXT`], its default.
+/// The result is sorted by range.
+#[must_use]
+pub fn merge_highlights(mut highlights: Vec<DocumentHighlight>) -> Vec<DocumentHighlight> {
+    let kind =
+        |highlight: &DocumentHighlight| highlight.kind.unwrap_or(DocumentHighlightKind::TEXT).0;
+    highlights
+        .sort_by_key(|highlight| (kind(highlight), highlight.range.start, highlight.range.end));
+
+    let mut merged: Vec<DocumentHighlight> = Vec::with_capacity(highlights.len());
+    for highlight in highlights {
+        if let Some(last) = merged.last_mut()
+            && kind(last) == kind(&highlight)
+            && (last.range.overlaps(&highlight.range) || last.range.end == highlight.range.start)
+        {
+            last.range.end = last.range.end.max(highlight.range.end);
+            continue;
+        }
+        merged.push(highlight);
+    }
+    merged.sort_by_key(|highlight| (highlight.range.start, highlight.range.end));
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    #[test]
+    fn test_merge_highlights() {
+        let highlight = |start, end, kind| DocumentHighlight {
+            range: Range::new(Position::new(0, start), Position::new(0, end)),
+            kind,
+        };
+        let read = Some(DocumentHighlightKind::READ);
+        let write = Some(DocumentHighlightKind::WRITE);
+
+        assert_eq!(
+            merge_highlights(vec![
+                highlight(4, 9, read),
+                highlight(0, 3, None),
+                highlight(2, 6, read),
+                highlight(3, 5, Some(DocumentHighlightKind::TEXT)),
+                highlight(6, 8, write),
+                highlight(12, 14, read),
+            ]),
+            vec![
+                highlight(0, 5, None),
+                highlight(2, 9, read),
+                highlight(6, 8, write),
+                highlight(12, 14, read),
+            ]
+        );
+    }
+}
@@ -41,6 +41,18 @@ pub struct DocumentLinkParams {
     pub partial_result_params: PartialResultParams,
 }
 
+impl DocumentLinkParams {
+    /// Creates params asking for the links in the document at `uri`.
+    #[must_use]
+    pub fn new(uri: Uri) -> Self {
+        Self {
+            text_document: TextDocumentIdentifier::new(uri),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        }
+    }
+}
+
 /// A document link is a range in a text document that links to an internal or external resource, like another
 /// text document or a web site.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
@@ -64,3 +76,22 @@ pub struct DocumentLink {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<Value>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        request::{DocumentLinkRequest, DocumentLinkResolve, Request},
+        tests::test_serialization,
+    };
+
+    #[test]
+    fn document_link_requests() {
+        assert_eq!(DocumentLinkRequest::METHOD, "textDocument/documentLink");
+        assert_eq!(DocumentLinkResolve::METHOD, "documentLink/resolve");
+        test_serialization(
+            &DocumentLinkParams::new("file:///README.md".parse().unwrap()),
+            r#"{"textDocument":{"uri":"file:///README.md"}}"#,
+        );
+    }
+}
@@ -111,6 +111,100 @@ pub struct DocumentSymbol {
     pub children: Option<Vec<DocumentSymbol>>,
 }
 
+impl DocumentSymbol {
+    #[must_use]
+    #[expect(deprecated)]
+    pub fn new(
+        name: impl Into<String>,
+        kind: SymbolKind,
+        range: Range,
+        selection_range: Range,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            detail: None,
+            kind,
+            tags: None,
+            deprecated: None,
+            range,
+            selection_range,
+            children: None,
+        }
+    }
+
+    /// Sets more detail for this symbol, e.g. the signature of a function.
+    #[must_use]
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the children of this symbol, e.g. the properties of a class.
+    #[must_use]
+    pub fn with_children(mut self, children: Vec<Self>) -> Self {
+        self.children = Some(children);
+        self
+    }
+
+    /// Recursively checks that the `selection_range` of every symbol is
+    /// contained in its `range`, and that the `range` of every child is
+    /// contained in the `range` of its parent.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first violation found, in depth-first order.
+    pub fn validate(&self) -> Result<(), SymbolError> {
+        if !contains(&self.range, &self.selection_range) {
+            return Err(SymbolError::SelectionRangeOutsideRange {
+                name: self.name.clone(),
+            });
+        }
+
+        for child in self.children.iter().flatten() {
+            if !contains(&self.range, &child.range) {
+                return Err(SymbolError::ChildOutsideParent {
+                    parent: self.name.clone(),
+                    child: child.name.clone(),
+                });
+            }
+            child.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+fn contains(outer: &Range, inner: &Range) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// An invariant of a [`DocumentSymbol`] tree that does not hold.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SymbolError {
+    /// The `selection_range` of the symbol is not contained in its `range`.
+    SelectionRangeOutsideRange { name: String },
+    /// The `range` of the child is not contained in the `range` of its parent.
+    ChildOutsideParent { parent: String, child: String },
+}
+
+impl std::fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SelectionRangeOutsideRange { name } => {
+                write!(f, "selection range of `{name}` is outside its range")
+            }
+            Self::ChildOutsideParent { parent, child } => {
+                write!(
+                    f,
+                    "range of `{child}` is outside the range of its parent `{parent}`"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SymbolError {}
+
 /// Represents information about programming constructs like variables, classes,
 /// interfaces etc.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
@@ -140,3 +234,92 @@ pub struct SymbolInformation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub container_name: Option<String>,
 }
+
+impl SymbolInformation {
+    #[must_use]
+    #[expect(deprecated)]
+    pub fn new(name: impl Into<String>, kind: SymbolKind, location: Location) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            tags: None,
+            deprecated: None,
+            location,
+            container_name: None,
+        }
+    }
+
+    /// Sets the name of the symbol containing this symbol.
+    #[must_use]
+    pub fn with_container(mut self, name: impl Into<String>) -> Self {
+        self.container_name = Some(name.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Position, tests::test_serialization};
+
+    fn symbol(name: &str, range: Range, selection_range: Range) -> DocumentSymbol {
+        DocumentSymbol::new(name, SymbolKind::FUNCTION, range, selection_range)
+    }
+
+    fn lines(start: u32, end: u32) -> Range {
+        Range::new(Position::new(start, 0), Position::new(end, 0))
+    }
+
+    #[test]
+    fn test_document_symbol_validate() {
+        let mut parent = symbol("parent", lines(0, 10), lines(0, 1));
+        parent.children = Some(vec![
+            symbol("first", lines(1, 4), lines(1, 2)),
+            symbol("second", lines(5, 10), lines(5, 6)),
+        ]);
+        assert_eq!(parent.validate(), Ok(()));
+
+        parent.children = Some(vec![symbol("child", lines(5, 12), lines(5, 6))]);
+        assert_eq!(
+            parent.validate(),
+            Err(SymbolError::ChildOutsideParent {
+                parent: "parent".into(),
+                child: "child".into(),
+            })
+        );
+
+        parent.children = Some(vec![symbol("child", lines(5, 8), lines(8, 9))]);
+        assert_eq!(
+            parent.validate(),
+            Err(SymbolError::SelectionRangeOutsideRange {
+                name: "child".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn document_symbol_tree() {
+        let tree = DocumentSymbol::new("Point", SymbolKind::STRUCT, lines(0, 4), lines(0, 1))
+            .with_children(vec![
+                DocumentSymbol::new("x", SymbolKind::FIELD, lines(1, 2), lines(1, 2))
+                    .with_detail("f64"),
+            ]);
+        assert_eq!(tree.validate(), Ok(()));
+        test_serialization(
+            &tree,
+            r#"{"name":"Point","kind":23,"range":{"start":{"line":0,"character":0},"end":{"line":4,"character":0}},"selectionRange":{"start":{"line":0,"character":0},"end":{"line":1,"character":0}},"children":[{"name":"x","detail":"f64","kind":8,"range":{"start":{"line":1,"character":0},"end":{"line":2,"character":0}},"selectionRange":{"start":{"line":1,"character":0},"end":{"line":2,"character":0}}}]}"#,
+        );
+    }
+
+    #[test]
+    fn symbol_information_with_container() {
+        let location = Location::new(
+            "file:///a.rs".parse().unwrap(),
+            Range::new(Position::new(1, 0), Position::new(3, 1)),
+        );
+        test_serialization(
+            &SymbolInformation::new("len", SymbolKind::METHOD, location).with_container("Vec"),
+            r#"{"name":"len","kind":6,"location":{"uri":"file:///a.rs","range":{"start":{"line":1,"character":0},"end":{"line":3,"character":1}}},"containerName":"Vec"}"#,
+        );
+    }
+}
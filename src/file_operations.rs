@@ -75,6 +75,20 @@ pub struct FileOperationRegistrationOptions {
     pub filters: Vec<FileOperationFilter>,
 }
 
+impl FileOperationRegistrationOptions {
+    /// Adds a filter matching `glob` in any scheme.
+    #[must_use]
+    pub fn glob(self, glob: impl Into<String>) -> Self {
+        self.with_filter(FileOperationFilter::glob(glob))
+    }
+
+    #[must_use]
+    pub fn with_filter(mut self, filter: FileOperationFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+}
+
 /// A filter to describe in which file operation requests or notifications
 /// the server is interested in.
 ///
@@ -83,12 +97,47 @@ pub struct FileOperationRegistrationOptions {
 #[serde(rename_all = "camelCase")]
 pub struct FileOperationFilter {
     /// A Uri like `file` or `untitled`.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub scheme: Option<String>,
 
     /// The actual file operation pattern.
     pub pattern: FileOperationPattern,
 }
 
+impl FileOperationFilter {
+    /// Creates a filter matching `glob` in any scheme.
+    #[must_use]
+    pub fn glob(glob: impl Into<String>) -> Self {
+        Self {
+            scheme: None,
+            pattern: FileOperationPattern::new(glob),
+        }
+    }
+
+    /// Restricts this filter to URIs with the given scheme, like `file`.
+    #[must_use]
+    pub fn with_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    /// Restricts this filter to files or folders only.
+    #[must_use]
+    pub const fn with_matches(mut self, matches: FileOperationPatternKind) -> Self {
+        self.pattern.matches = Some(matches);
+        self
+    }
+
+    /// Makes the glob pattern match ignoring casing.
+    #[must_use]
+    pub const fn ignore_case(mut self) -> Self {
+        self.pattern.options = Some(FileOperationPatternOptions {
+            ignore_case: Some(true),
+        });
+        self
+    }
+}
+
 /// A pattern kind describing if a glob pattern matches a file a folder or
 /// both.
 ///
@@ -146,6 +195,16 @@ pub struct FileOperationPattern {
     pub options: Option<FileOperationPatternOptions>,
 }
 
+impl FileOperationPattern {
+    #[must_use]
+    pub fn new(glob: impl Into<String>) -> Self {
+        Self {
+            glob: glob.into(),
+            ..Self::default()
+        }
+    }
+}
+
 /// The parameters sent in notifications/requests for user-initiated creation
 /// of files.
 ///
@@ -211,3 +270,24 @@ pub struct FileDelete {
     /// A file:// URI for the location of the file/folder being deleted.
     pub uri: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_serialization;
+
+    #[test]
+    fn file_operation_registration_options_builder() {
+        test_serialization(
+            &FileOperationRegistrationOptions::default()
+                .glob("**/*.rs")
+                .with_filter(
+                    FileOperationFilter::glob("**/target")
+                        .with_scheme("file")
+                        .with_matches(FileOperationPatternKind::Folder)
+                        .ignore_case(),
+                ),
+            r#"{"filters":[{"pattern":{"glob":"**/*.rs"}},{"scheme":"file","pattern":{"glob":"**/target","matches":"folder","options":{"ignoreCase":true}}}]}"#,
+        );
+    }
+}
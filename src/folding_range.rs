@@ -153,3 +153,63 @@ pub struct FoldingRange {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub collapsed_text: Option<String>,
 }
+
+impl FoldingRange {
+    /// Creates a folding range spanning the given lines.
+    #[must_use]
+    pub const fn new(start_line: u32, end_line: u32) -> Self {
+        Self {
+            start_line,
+            start_character: None,
+            end_line,
+            end_character: None,
+            kind: None,
+            collapsed_text: None,
+        }
+    }
+
+    /// Sets the character offsets at which the folded range starts and ends.
+    #[must_use]
+    pub const fn with_chars(mut self, start_character: u32, end_character: u32) -> Self {
+        self.start_character = Some(start_character);
+        self.end_character = Some(end_character);
+        self
+    }
+
+    /// Sets the kind of the folding range.
+    #[must_use]
+    pub const fn with_kind(mut self, kind: FoldingRangeKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Strips the character offsets, for clients that signal `lineFoldingOnly`.
+    #[must_use]
+    pub const fn line_only(mut self) -> Self {
+        self.start_character = None;
+        self.end_character = None;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_serialization;
+
+    #[test]
+    fn test_folding_range_builder() {
+        let range = FoldingRange::new(1, 4)
+            .with_chars(2, 8)
+            .with_kind(FoldingRangeKind::Region);
+        test_serialization(
+            &range,
+            r#"{"startLine":1,"startCharacter":2,"endLine":4,"endCharacter":8,"kind":"region"}"#,
+        );
+
+        test_serialization(
+            &range.line_only(),
+            r#"{"startLine":1,"endLine":4,"kind":"region"}"#,
+        );
+    }
+}
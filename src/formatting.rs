@@ -23,6 +23,28 @@ pub struct DocumentOnTypeFormattingOptions {
     pub more_trigger_character: Option<Vec<String>>,
 }
 
+impl DocumentOnTypeFormattingOptions {
+    /// Creates options triggering formatting on `first_trigger_character`.
+    #[must_use]
+    pub fn new(first_trigger_character: impl Into<String>) -> Self {
+        Self {
+            first_trigger_character: first_trigger_character.into(),
+            more_trigger_character: None,
+        }
+    }
+
+    /// Sets the characters, besides the first one, that trigger formatting.
+    #[must_use]
+    pub fn more_trigger_characters<I>(mut self, characters: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.more_trigger_character = Some(characters.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentFormattingParams {
@@ -63,6 +85,29 @@ pub struct FormattingOptions {
     pub trim_final_newlines: Option<bool>,
 }
 
+impl FormattingOptions {
+    /// Sets whether trailing whitespace on a line is trimmed.
+    #[must_use]
+    pub const fn with_trim_trailing_whitespace(mut self, trim: bool) -> Self {
+        self.trim_trailing_whitespace = Some(trim);
+        self
+    }
+
+    /// Sets whether a final newline is inserted if one does not exist.
+    #[must_use]
+    pub const fn with_insert_final_newline(mut self, insert: bool) -> Self {
+        self.insert_final_newline = Some(insert);
+        self
+    }
+
+    /// Sets whether newlines after the final newline are trimmed.
+    #[must_use]
+    pub const fn with_trim_final_newlines(mut self, trim: bool) -> Self {
+        self.trim_final_newlines = Some(trim);
+        self
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum FormattingProperty {
@@ -150,4 +195,37 @@ mod tests {
             r#"{"tabSize":123,"insertSpaces":true,"prop":1}"#,
         );
     }
+
+    #[test]
+    fn formatting_options_well_known_properties() {
+        let options = FormattingOptions {
+            tab_size: 4,
+            insert_spaces: true,
+            ..Default::default()
+        }
+        .with_trim_trailing_whitespace(true)
+        .with_insert_final_newline(true)
+        .with_trim_final_newlines(false);
+
+        let json = r#"{"tabSize":4,"insertSpaces":true,"trimTrailingWhitespace":true,"insertFinalNewline":true,"trimFinalNewlines":false}"#;
+        test_serialization(&options, json);
+
+        let parsed: FormattingOptions = serde_json::from_str(json).unwrap();
+        assert!(parsed.properties.is_empty());
+        assert_eq!(parsed.trim_trailing_whitespace, Some(true));
+        assert_eq!(parsed.insert_final_newline, Some(true));
+        assert_eq!(parsed.trim_final_newlines, Some(false));
+    }
+
+    #[test]
+    fn on_type_formatting_options() {
+        test_serialization(
+            &DocumentOnTypeFormattingOptions::new("\n"),
+            r#"{"firstTriggerCharacter":"\n"}"#,
+        );
+        test_serialization(
+            &DocumentOnTypeFormattingOptions::new("}").more_trigger_characters([";", ")"]),
+            r#"{"firstTriggerCharacter":"}","moreTriggerCharacter":[";",")"]}"#,
+        );
+    }
 }
@@ -136,6 +136,55 @@ pub struct InlayHint {
     pub data: Option<LSPAny>,
 }
 
+impl InlayHint {
+    /// Whether this hint carries any of the properties a client may resolve
+    /// lazily with an `inlayHint/resolve` request: its `tooltip`, its
+    /// `text_edits`, or the tooltip, location or command of a label part.
+    #[must_use]
+    pub fn resolved(&self) -> bool {
+        let has_resolved_part = match &self.label {
+            InlayHintLabel::String(_) => false,
+            InlayHintLabel::LabelParts(parts) => parts.iter().any(|part| {
+                part.tooltip.is_some() || part.location.is_some() || part.command.is_some()
+            }),
+        };
+        self.tooltip.is_some() || self.text_edits.is_some() || has_resolved_part
+    }
+
+    /// Fills the lazily resolved properties missing from this hint with
+    /// those of `resolved`, the response to an `inlayHint/resolve` request.
+    ///
+    /// Label parts are merged by index if both hints have the same number of
+    /// parts. Properties already set on this hint are kept.
+    pub fn merge_resolved(&mut self, resolved: InlayHint) {
+        if self.tooltip.is_none() {
+            self.tooltip = resolved.tooltip;
+        }
+        if self.text_edits.is_none() {
+            self.text_edits = resolved.text_edits;
+        }
+        if self.data.is_none() {
+            self.data = resolved.data;
+        }
+        if let (InlayHintLabel::LabelParts(parts), InlayHintLabel::LabelParts(resolved_parts)) =
+            (&mut self.label, resolved.label)
+            && parts.len() == resolved_parts.len()
+        {
+            for (part, resolved_part) in parts.iter_mut().zip(resolved_parts) {
+                if part.tooltip.is_none() {
+                    part.tooltip = resolved_part.tooltip;
+                }
+                if part.location.is_none() {
+                    part.location = resolved_part.location;
+                }
+                if part.command.is_none() {
+                    part.command = resolved_part.command;
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum InlayHintLabel {
@@ -278,4 +327,33 @@ pub struct InlayHintWorkspaceClientCapabilities {
     pub refresh_support: Option<bool>,
 }
 
-// TODO(sno2): add tests once stabilized
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_resolved() {
+        let mut hint: InlayHint = serde_json::from_str(
+            r#"{"position":{"line":0,"character":4},"label":[{"value":": "},{"value":"Vec<u8>"}],"data":1}"#,
+        )
+        .unwrap();
+        assert!(!hint.resolved());
+
+        let resolved: InlayHint = serde_json::from_str(
+            r#"{"position":{"line":0,"character":4},"label":[{"value":": "},{"value":"Vec<u8>","tooltip":"alloc::vec::Vec"}],"tooltip":"inferred type","data":2}"#,
+        )
+        .unwrap();
+        hint.merge_resolved(resolved);
+
+        assert!(hint.resolved());
+        assert_eq!(
+            serde_json::to_value(&hint).unwrap(),
+            serde_json::json!({
+                "position": {"line": 0, "character": 4},
+                "label": [{"value": ": "}, {"value": "Vec<u8>", "tooltip": "alloc::vec::Vec"}],
+                "tooltip": "inferred type",
+                "data": 1,
+            })
+        );
+    }
+}
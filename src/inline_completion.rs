@@ -55,6 +55,23 @@ pub struct InlineCompletionParams {
     pub context: InlineCompletionContext,
 }
 
+impl InlineCompletionParams {
+    /// Creates inline completion parameters for the given position and context.
+    #[must_use]
+    pub const fn new(
+        text_document_position: TextDocumentPositionParams,
+        context: InlineCompletionContext,
+    ) -> Self {
+        Self {
+            work_done_progress_params: WorkDoneProgressParams {
+                work_done_token: None,
+            },
+            text_document_position,
+            context,
+        }
+    }
+}
+
 /// Describes how an [`InlineCompletionItemProvider`] was triggered.
 ///
 /// @since 3.18.0
@@ -85,6 +102,13 @@ pub struct SelectedCompletionInfo {
     pub text: String,
 }
 
+impl SelectedCompletionInfo {
+    #[must_use]
+    pub const fn new(range: Range, text: String) -> Self {
+        Self { range, text }
+    }
+}
+
 /// Provides information about the context in which an inline completion was
 /// requested.
 ///
@@ -110,6 +134,30 @@ pub struct InlineCompletionContext {
     pub selected_completion_info: Option<SelectedCompletionInfo>,
 }
 
+impl InlineCompletionContext {
+    /// Creates a context without a selected completion item.
+    #[must_use]
+    pub const fn new(trigger_kind: InlineCompletionTriggerKind) -> Self {
+        Self {
+            trigger_kind,
+            selected_completion_info: None,
+        }
+    }
+
+    /// Creates a context for a request made while an item is selected in the
+    /// completion widget.
+    #[must_use]
+    pub const fn with_selected_completion_info(
+        trigger_kind: InlineCompletionTriggerKind,
+        selected_completion_info: SelectedCompletionInfo,
+    ) -> Self {
+        Self {
+            trigger_kind,
+            selected_completion_info: Some(selected_completion_info),
+        }
+    }
+}
+
 /// `InlineCompletion` response can be multiple completion items, or a list of completion items
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -127,6 +175,13 @@ pub struct InlineCompletionList {
     pub items: Vec<InlineCompletionItem>,
 }
 
+impl InlineCompletionList {
+    #[must_use]
+    pub const fn new(items: Vec<InlineCompletionItem>) -> Self {
+        Self { items }
+    }
+}
+
 /// An inline completion item represents a text snippet that is proposed inline
 /// to complete text that is being typed.
 ///
@@ -161,3 +216,87 @@ pub struct InlineCompletionItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub insert_text_format: Option<InsertTextFormat>,
 }
+
+impl InlineCompletionItem {
+    /// Creates an item that inserts `insert_text` at the cursor position.
+    #[must_use]
+    pub const fn new(insert_text: String) -> Self {
+        Self {
+            insert_text,
+            filter_text: None,
+            range: None,
+            command: None,
+            insert_text_format: None,
+        }
+    }
+
+    /// Sets the range replaced by this item.
+    #[must_use]
+    pub const fn with_range(mut self, range: Range) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Sets the text used to decide whether this item should be shown.
+    #[must_use]
+    pub fn with_filter_text(mut self, filter_text: impl Into<String>) -> Self {
+        self.filter_text = Some(filter_text.into());
+        self
+    }
+
+    /// Sets the command executed after this item is inserted.
+    #[must_use]
+    pub fn with_command(mut self, command: Command) -> Self {
+        self.command = Some(command);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_serialization;
+    use crate::{Position, TextDocumentIdentifier, Uri};
+    use std::str::FromStr;
+
+    #[test]
+    fn inline_completion_params() {
+        let range = Range::new(Position::new(0, 8), Position::new(0, 9));
+        let params = InlineCompletionParams::new(
+            TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(Uri::from_str("file:///a.ts").unwrap()),
+                Position::new(0, 9),
+            ),
+            InlineCompletionContext::with_selected_completion_info(
+                InlineCompletionTriggerKind::AUTOMATIC,
+                SelectedCompletionInfo::new(range, ".log".to_string()),
+            ),
+        );
+        let json = r#"{"textDocument":{"uri":"file:///a.ts"},"position":{"line":0,"character":9},"context":{"triggerKind":2,"selectedCompletionInfo":{"range":{"start":{"line":0,"character":8},"end":{"line":0,"character":9}},"text":".log"}}}"#;
+        test_serialization(&params, json);
+
+        let context = InlineCompletionContext::new(InlineCompletionTriggerKind::INVOKED);
+        test_serialization(&context, r#"{"triggerKind":1}"#);
+    }
+
+    #[test]
+    fn inline_completion_response() {
+        let item = InlineCompletionItem::new(".log()".to_string())
+            .with_range(Range::new(Position::new(0, 8), Position::new(0, 9)))
+            .with_filter_text(".log")
+            .with_command(Command::new("accept".into(), "editor.accept".into(), None));
+        let list = InlineCompletionResponse::List(InlineCompletionList::new(vec![item.clone()]));
+        let item_json = r#"{"insertText":".log()","filterText":".log","range":{"start":{"line":0,"character":8},"end":{"line":0,"character":9}},"command":{"title":"accept","command":"editor.accept"}}"#;
+        let list_json = format!(r#"{{"items":[{item_json}]}}"#);
+        test_serialization(&list, &list_json);
+
+        let array = InlineCompletionResponse::Array(vec![item]);
+        let array_json = format!("[{item_json}]");
+        test_serialization(&array, &array_json);
+
+        test_serialization(
+            &InlineCompletionItem::new("x".to_string()),
+            r#"{"insertText":"x"}"#,
+        );
+    }
+}
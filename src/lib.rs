@@ -2,14 +2,18 @@
 //!
 //! Based on <https://microsoft.github.io/language-server-protocol/specification>
 
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+};
 
 use serde::{Deserialize, Serialize, de, de::Error};
 use serde_json::Value;
 
 mod macros;
 
-pub use uri::Uri;
+pub use uri::{BuildCachedUriHasher, CachedUri, CachedUriHasher, Uri};
 mod uri;
 
 pub mod error_codes;
@@ -118,10 +122,30 @@ use crate::macros::lsp_enum;
 #[derive(Debug, Eq, Hash, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum NumberOrString {
-    Number(i32),
+    /// A numeric value.
+    ///
+    /// JSON-RPC request ids are not bound to `i32`, so this is wide enough to
+    /// hold ids beyond `i32::MAX` sent by some clients.
+    Number(i64),
     String(String),
 }
 
+impl NumberOrString {
+    /// Whether this id and `other` denote the same request, e.g. to look up
+    /// the request a `CancelParams::id` refers to.
+    ///
+    /// Besides equal ids, a number matches a string holding its decimal
+    /// representation, since some clients echo numeric ids back as strings.
+    #[must_use]
+    pub fn matches_id(&self, other: &NumberOrString) -> bool {
+        match (self, other) {
+            (Self::Number(number), Self::String(string))
+            | (Self::String(string), Self::Number(number)) => *string == number.to_string(),
+            _ => self == other,
+        }
+    }
+}
+
 impl From<String> for NumberOrString {
     fn from(value: String) -> Self {
         Self::String(value)
@@ -136,6 +160,12 @@ impl From<&str> for NumberOrString {
 
 impl From<i32> for NumberOrString {
     fn from(value: i32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<i64> for NumberOrString {
+    fn from(value: i64) -> Self {
         Self::Number(value)
     }
 }
@@ -165,6 +195,72 @@ pub type LSPObject = serde_json::Map<String, serde_json::Value>;
 /// @since 3.17.0
 pub type LSPArray = Vec<serde_json::Value>;
 
+/// Deserializes `bytes` like [`serde_json::from_slice`], but first strips a
+/// leading UTF-8 byte order mark, which some clients and captured fixtures
+/// include.
+///
+/// # Errors
+///
+/// Returns an error if the remaining bytes are not valid JSON for `T`.
+pub fn from_json_lenient<T: de::DeserializeOwned>(bytes: &[u8]) -> serde_json::Result<T> {
+    serde_json::from_slice(bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes))
+}
+
+/// Items carrying an opaque `data` field that the client sends back unchanged
+/// in the matching resolve request, e.g. `completionItem/resolve`.
+///
+/// The provided methods let servers store and read back a typed payload.
+pub trait ResolveData {
+    /// The `data` field of the item.
+    fn resolve_data(&self) -> Option<&LSPAny>;
+
+    /// A mutable reference to the `data` field of the item.
+    fn resolve_data_mut(&mut self) -> &mut Option<LSPAny>;
+
+    /// Serializes `data` into the `data` field, replacing any previous value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` fails to serialize, in which case the field
+    /// is left unchanged.
+    fn set_data<T: Serialize>(&mut self, data: &T) -> serde_json::Result<()> {
+        *self.resolve_data_mut() = Some(serde_json::to_value(data)?);
+        Ok(())
+    }
+
+    /// Deserializes the `data` field into `T`.
+    ///
+    /// Returns `None` if the item has no data, or the result of deserializing
+    /// it otherwise.
+    fn data_as<T: de::DeserializeOwned>(&self) -> Option<serde_json::Result<T>> {
+        self.resolve_data().map(T::deserialize)
+    }
+}
+
+macro_rules! resolve_data {
+    ($($item:ty),* $(,)?) => {
+        $(
+            impl ResolveData for $item {
+                fn resolve_data(&self) -> Option<&LSPAny> {
+                    self.data.as_ref()
+                }
+
+                fn resolve_data_mut(&mut self) -> &mut Option<LSPAny> {
+                    &mut self.data
+                }
+            }
+        )*
+    };
+}
+
+resolve_data!(
+    CodeAction,
+    CodeLens,
+    CompletionItem,
+    DocumentLink,
+    InlayHint
+);
+
 /// Position in a text document expressed as zero-based line and character offset.
 /// A position is between two characters like an 'insert' cursor in a editor.
 #[derive(
@@ -186,6 +282,37 @@ impl Position {
     pub const fn new(line: u32, character: u32) -> Self {
         Self { line, character }
     }
+
+    /// Converts signed coordinates, e.g. from a parser using `i64` offsets,
+    /// to a position.
+    ///
+    /// Returns `None` if either coordinate is negative or exceeds `u32::MAX`.
+    /// Note that deserializing a position with negative fields fails likewise.
+    #[must_use]
+    pub fn try_from_signed(line: i64, character: i64) -> Option<Self> {
+        Some(Self::new(
+            u32::try_from(line).ok()?,
+            u32::try_from(character).ok()?,
+        ))
+    }
+
+    /// Whether this position comes strictly before `other` in the document.
+    #[must_use]
+    pub fn is_before(&self, other: &Position) -> bool {
+        self < other
+    }
+
+    /// Whether this position comes strictly after `other` in the document.
+    #[must_use]
+    pub fn is_after(&self, other: &Position) -> bool {
+        self > other
+    }
+
+    /// Whether this position lies between `start` and `end`, both included.
+    #[must_use]
+    pub fn is_between(&self, start: &Position, end: &Position) -> bool {
+        start <= self && self <= end
+    }
 }
 
 /// A range in a text document expressed as (zero-based) start and end positions.
@@ -203,6 +330,251 @@ impl Range {
     pub const fn new(start: Position, end: Position) -> Self {
         Self { start, end }
     }
+
+    /// Whether this range and `other` share some text. Ranges that only touch,
+    /// like `0:0-0:3` and `0:3-0:5`, do not overlap.
+    #[must_use]
+    pub fn overlaps(&self, other: &Range) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// Converts a byte offset into `text` to a [`Position`] whose `character`
+/// is counted in the code units of `encoding`.
+///
+/// Lines are terminated by `\n` or `\r\n`. An offset past the end of `text`
+/// is clamped to it, and one inside a character or a `\r\n` terminator to
+/// the preceding boundary. Encodings other than UTF-8 and UTF-32 are
+/// treated as UTF-16, the protocol default.
+#[must_use]
+pub fn position_from_byte_offset(
+    text: &str,
+    byte_offset: usize,
+    encoding: &PositionEncodingKind,
+) -> Position {
+    let mut offset = byte_offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.bytes().filter(|&b| b == b'\n').count();
+    let content = &before[line_start..];
+    let content = if text[offset..].starts_with('\n') {
+        content.strip_suffix('\r').unwrap_or(content)
+    } else {
+        content
+    };
+    let character: usize = content.chars().map(|c| code_units(c, encoding)).sum();
+    Position::new(
+        u32::try_from(line).unwrap_or(u32::MAX),
+        u32::try_from(character).unwrap_or(u32::MAX),
+    )
+}
+
+/// Converts `position` to a byte offset into `text`, the inverse of
+/// [`position_from_byte_offset`].
+///
+/// A line past the end of `text` maps to its length, and a character past
+/// the end of a line to the end of that line's content, before its
+/// terminator. A character inside a multi-unit character (e.g. between the
+/// halves of a UTF-16 surrogate pair) maps to the start of that character.
+#[must_use]
+pub fn byte_offset_from_position(
+    text: &str,
+    position: Position,
+    encoding: &PositionEncodingKind,
+) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return text.len(),
+        }
+    }
+    let line = &text[line_start..];
+    let line = line.find('\n').map_or(line, |end| &line[..end]);
+    let line = line.strip_suffix('\r').unwrap_or(line);
+
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        units += code_units(c, encoding);
+        if units > position.character as usize {
+            return line_start + i;
+        }
+    }
+    line_start + line.len()
+}
+
+/// Converts a byte span of `text`, e.g. from a parser, to a [`Range`] in
+/// `encoding`, with the same clamping as [`position_from_byte_offset`].
+#[must_use]
+pub fn range_from_byte_span(
+    text: &str,
+    span: std::ops::Range<usize>,
+    encoding: &PositionEncodingKind,
+) -> Range {
+    Range::new(
+        position_from_byte_offset(text, span.start, encoding),
+        position_from_byte_offset(text, span.end, encoding),
+    )
+}
+
+/// Computes the edits turning `old` into `new`, with ranges in `encoding`, so
+/// that e.g. a formatter can return what changed instead of replacing the
+/// whole document.
+///
+/// Lines are matched by a longest common subsequence, and each run of
+/// changed lines is narrowed to the characters that actually differ. The
+/// edits are in document order and their ranges refer to `old`.
+///
+/// Finding that subsequence takes memory proportional to the product of the
+/// numbers of changed lines. Past a fixed limit, the changed lines are
+/// replaced by a single edit instead.
+#[must_use]
+pub fn diff_text_edits(old: &str, new: &str, encoding: &PositionEncodingKind) -> Vec<TextEdit> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let line_starts = |lines: &[&str]| {
+        let mut starts = vec![0];
+        starts.extend(lines.iter().scan(0, |offset, line| {
+            *offset += line.len();
+            Some(*offset)
+        }));
+        starts
+    };
+    let (old_starts, new_starts) = (line_starts(&old_lines), line_starts(&new_lines));
+
+    diff_lines(&old_lines, &new_lines)
+        .into_iter()
+        .map(|(old_hunk, new_hunk)| {
+            let mut old_span = old_starts[old_hunk.start]..old_starts[old_hunk.end];
+            let mut new_span = new_starts[new_hunk.start]..new_starts[new_hunk.end];
+
+            let prefix = common_prefix_len(&old[old_span.clone()], &new[new_span.clone()]);
+            old_span.start += prefix;
+            new_span.start += prefix;
+            let suffix = common_suffix_len(&old[old_span.clone()], &new[new_span.clone()]);
+            old_span.end -= suffix;
+            new_span.end -= suffix;
+
+            TextEdit::new(
+                range_from_byte_span(old, old_span, encoding),
+                new[new_span].to_string(),
+            )
+        })
+        .collect()
+}
+
+/// The largest line LCS table [`diff_lines`] builds, about 32 MB.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// The runs of lines that differ between `old` and `new`, as pairs of
+/// replaced and replacing line index ranges.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_mid, new_mid) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    if old_mid.is_empty() && new_mid.is_empty() {
+        return Vec::new();
+    }
+    if old_mid
+        .len()
+        .checked_mul(new_mid.len())
+        .is_none_or(|cells| cells > MAX_DIFF_CELLS)
+    {
+        return vec![(prefix..old.len() - suffix, prefix..new.len() - suffix)];
+    }
+
+    // lcs[i][j] is the length of the longest common subsequence of
+    // `old_mid[i..]` and `new_mid[j..]`
+    let mut lcs = vec![vec![0_usize; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let mut hunk: Option<(usize, usize)> = None;
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+            if let Some((start_i, start_j)) = hunk.take() {
+                hunks.push((prefix + start_i..prefix + i, prefix + start_j..prefix + j));
+            }
+            i += 1;
+            j += 1;
+            continue;
+        }
+        hunk.get_or_insert((i, j));
+        if j == new_mid.len() || (i < old_mid.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    if let Some((start_i, start_j)) = hunk {
+        hunks.push((prefix + start_i..prefix + i, prefix + start_j..prefix + j));
+    }
+    hunks
+}
+
+/// The byte length of the longest common prefix of `a` and `b` that does
+/// not split a character or a `\r\n` line terminator.
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or_else(|| a.len().min(b.len()), |((i, _), _)| i);
+    if a[..len].ends_with('\r') {
+        len - 1
+    } else {
+        len
+    }
+}
+
+/// The byte length of the longest common suffix of `a` and `b` that does
+/// not split a character or a `\r\n` line terminator.
+fn common_suffix_len(a: &str, b: &str) -> usize {
+    let len: usize = a
+        .chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum();
+    let before = &a[..a.len() - len];
+    if before.ends_with('\r') && a[a.len() - len..].starts_with('\n') {
+        len - 1
+    } else {
+        len
+    }
+}
+
+/// The number of code units of `c` in `encoding`.
+fn code_units(c: char, encoding: &PositionEncodingKind) -> usize {
+    if encoding.is_utf8() {
+        c.len_utf8()
+    } else if encoding.is_utf32() {
+        1
+    } else {
+        c.len_utf16()
+    }
 }
 
 /// Represents a location inside a resource, such as a line inside a text file.
@@ -273,6 +645,29 @@ impl PositionEncodingKind {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    #[must_use]
+    pub fn is_utf8(&self) -> bool {
+        *self == Self::UTF8
+    }
+
+    #[must_use]
+    pub fn is_utf16(&self) -> bool {
+        *self == Self::UTF16
+    }
+
+    #[must_use]
+    pub fn is_utf32(&self) -> bool {
+        *self == Self::UTF32
+    }
+}
+
+/// Defaults to [`PositionEncodingKind::UTF16`], the encoding used when none
+/// was negotiated.
+impl Default for PositionEncodingKind {
+    fn default() -> Self {
+        Self::UTF16
+    }
 }
 
 impl From<String> for PositionEncodingKind {
@@ -341,6 +736,55 @@ pub struct CodeDescription {
     pub href: Uri,
 }
 
+impl CodeDescription {
+    /// Parses `url` into a code description, which must be openable in a browser.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `url` is not a valid URI or if its scheme is neither
+    /// `http` nor `https`.
+    pub fn from_url(url: &str) -> Result<Self, CodeDescriptionError> {
+        let href: Uri = url.parse().map_err(CodeDescriptionError::Parse)?;
+        let scheme = href.scheme().as_str();
+        if !(scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")) {
+            return Err(CodeDescriptionError::UnsupportedScheme(scheme.to_string()));
+        }
+        Ok(Self { href })
+    }
+}
+
+/// The reason a URL was rejected by [`CodeDescription::from_url`].
+#[derive(Debug, Clone)]
+pub enum CodeDescriptionError {
+    /// The URL is not a valid URI.
+    Parse(fluent_uri::ParseError),
+    /// The URL's scheme is neither `http` nor `https`.
+    UnsupportedScheme(String),
+}
+
+impl std::fmt::Display for CodeDescriptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "invalid code description URL: {err}"),
+            Self::UnsupportedScheme(scheme) => {
+                write!(
+                    f,
+                    "code description URL must use http or https, not `{scheme}`"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodeDescriptionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::UnsupportedScheme(_) => None,
+        }
+    }
+}
+
 impl Diagnostic {
     #[must_use]
     pub fn new(
@@ -377,9 +821,63 @@ impl Diagnostic {
         source: Option<String>,
         message: String,
     ) -> Self {
-        let code = Some(NumberOrString::Number(code_number));
+        let code = Some(NumberOrString::from(code_number));
         Self::new(range, Some(severity), code, source, message, None, None)
     }
+
+    /// Formats the code of this diagnostic for display, prefixed by its
+    /// source if any, e.g. `rustc(E0308)` or `E0308`.
+    ///
+    /// Returns `None` if the diagnostic has no code.
+    #[must_use]
+    pub fn display_code(&self) -> Option<String> {
+        let code = match self.code.as_ref()? {
+            NumberOrString::Number(number) => number.to_string(),
+            NumberOrString::String(string) => string.clone(),
+        };
+        Some(match &self.source {
+            Some(source) => format!("{source}({code})"),
+            None => code,
+        })
+    }
+
+    /// The diagnostic's message as plain text.
+    #[must_use]
+    pub fn message_text(&self) -> &str {
+        &self.message
+    }
+
+    /// Whether the diagnostic is tagged with `tag`.
+    #[must_use]
+    pub fn has_tag(&self, tag: DiagnosticTag) -> bool {
+        self.tags.as_ref().is_some_and(|tags| tags.contains(&tag))
+    }
+
+    /// Tags the diagnostic with `tag` unless it already is, since clients may
+    /// render duplicate tags oddly.
+    ///
+    /// Returns whether the tag was added.
+    pub fn add_tag(&mut self, tag: DiagnosticTag) -> bool {
+        if self.has_tag(tag) {
+            return false;
+        }
+        self.tags.get_or_insert_with(Vec::new).push(tag);
+        true
+    }
+}
+
+/// Keeps the diagnostics that are at least as severe as `min`, e.g. only errors
+/// and warnings for `DiagnosticSeverity::WARNING`.
+///
+/// Diagnostics without a severity are kept, since their interpretation is left
+/// to the client.
+#[must_use]
+pub fn filter_by_severity(diags: Vec<Diagnostic>, min: DiagnosticSeverity) -> Vec<Diagnostic> {
+    diags
+        .into_iter()
+        // severities are ordered from most (`ERROR = 1`) to least severe
+        .filter(|diag| diag.severity.is_none_or(|severity| severity <= min))
+        .collect()
 }
 
 /// The protocol currently supports the following diagnostic severities:
@@ -413,7 +911,7 @@ pub struct DiagnosticRelatedInformation {
 }
 
 /// The diagnostic tags.
-#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct DiagnosticTag(i32);
 
@@ -479,6 +977,93 @@ impl TextEdit {
     pub const fn new(range: Range, new_text: String) -> Self {
         Self { range, new_text }
     }
+
+    /// Whether applying this edit leaves the document unchanged, given the
+    /// text currently covered by `range`: an empty insertion, or a replacement
+    /// by identical text.
+    #[must_use]
+    pub fn is_noop(&self, current_text_in_range: &str) -> bool {
+        (self.range.start == self.range.end && self.new_text.is_empty())
+            || self.new_text == current_text_in_range
+    }
+
+    /// Rewrites every line ending of `new_text`, be it `\r\n`, `\n` or `\r`,
+    /// to `eol`.
+    ///
+    /// Clients setting `normalizesLineEndings` in their
+    /// [`WorkspaceEditClientCapabilities`] do this themselves; for the others,
+    /// servers should use the line ending of the edited document.
+    pub fn normalize_line_endings(&mut self, eol: &str) {
+        if !self.new_text.contains(['\r', '\n']) {
+            return;
+        }
+
+        let mut normalized = String::with_capacity(self.new_text.len());
+        let mut chars = self.new_text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\r' => {
+                    chars.next_if_eq(&'\n');
+                    normalized.push_str(eol);
+                }
+                '\n' => normalized.push_str(eol),
+                c => normalized.push(c),
+            }
+        }
+        self.new_text = normalized;
+    }
+}
+
+/// An invalid set of text edits.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum EditError {
+    /// Two edits modify overlapping ranges, which the protocol forbids.
+    Overlap { first: Range, second: Range },
+}
+
+impl std::fmt::Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overlap { first, second } => write!(
+                f,
+                "edit at {}:{}-{}:{} overlaps edit at {}:{}-{}:{}",
+                first.start.line,
+                first.start.character,
+                first.end.line,
+                first.end.character,
+                second.start.line,
+                second.start.character,
+                second.end.line,
+                second.end.character,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Checks that no two of `ranges` overlap. Touching ranges, such as an
+/// insertion right at the end of a replaced range, are allowed.
+pub(crate) fn check_overlap(ranges: impl IntoIterator<Item = Range>) -> Result<(), EditError> {
+    let mut ranges: Vec<Range> = ranges.into_iter().collect();
+    ranges.sort_by_key(|range| (range.start, range.end));
+
+    let mut furthest: Option<Range> = None;
+    for range in ranges {
+        if let Some(previous) = furthest {
+            if range.overlaps(&previous) {
+                return Err(EditError::Overlap {
+                    first: previous,
+                    second: range,
+                });
+            }
+            if previous.end >= range.end {
+                continue;
+            }
+        }
+        furthest = Some(range);
+    }
+    Ok(())
 }
 
 /// An identifier referring to a change annotation managed by a workspace
@@ -515,9 +1100,37 @@ pub struct TextDocumentEdit {
     ///
     /// @since 3.16.0 - support for `AnnotatedTextEdit`. This is guarded by the
     /// client capability `workspace.workspaceEdit.changeAnnotationSupport`
+    #[serde(deserialize_with = "TextDocumentEdit::deserialize_edits")]
     pub edits: Vec<OneOf<TextEdit, AnnotatedTextEdit>>,
 }
 
+impl TextDocumentEdit {
+    /// `TextEdit` is a structural subset of `AnnotatedTextEdit`, so the untagged
+    /// `OneOf` would read every annotated edit as a plain one, dropping its
+    /// `annotationId`. Try the annotated edit first instead.
+    fn deserialize_edits<'de, D>(
+        deserializer: D,
+    ) -> Result<Vec<OneOf<TextEdit, AnnotatedTextEdit>>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Edit {
+            Annotated(AnnotatedTextEdit),
+            Plain(TextEdit),
+        }
+
+        Ok(Vec::<Edit>::deserialize(deserializer)?
+            .into_iter()
+            .map(|edit| match edit {
+                Edit::Annotated(edit) => OneOf::Right(edit),
+                Edit::Plain(edit) => OneOf::Left(edit),
+            })
+            .collect())
+    }
+}
+
 /// Additional information that describes document changes.
 ///
 /// @since 3.16.0
@@ -578,6 +1191,45 @@ pub struct CreateFile {
     pub annotation_id: Option<ChangeAnnotationIdentifier>,
 }
 
+impl CreateFile {
+    #[must_use]
+    pub const fn new(uri: Uri) -> Self {
+        Self {
+            uri,
+            options: None,
+            annotation_id: None,
+        }
+    }
+
+    fn options_mut(&mut self) -> &mut CreateFileOptions {
+        self.options.get_or_insert(CreateFileOptions {
+            overwrite: None,
+            ignore_if_exists: None,
+        })
+    }
+
+    /// Overwrites the file if it exists. This wins over `ignore_if_exists`.
+    #[must_use]
+    pub fn overwrite(mut self) -> Self {
+        self.options_mut().overwrite = Some(true);
+        self
+    }
+
+    /// Skips the operation if the file exists.
+    #[must_use]
+    pub fn ignore_if_exists(mut self) -> Self {
+        self.options_mut().ignore_if_exists = Some(true);
+        self
+    }
+
+    /// Describes the operation with the change annotation `id`.
+    #[must_use]
+    pub fn annotation(mut self, id: impl Into<ChangeAnnotationIdentifier>) -> Self {
+        self.annotation_id = Some(id.into());
+        self
+    }
+}
+
 /// Rename file options
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -609,6 +1261,46 @@ pub struct RenameFile {
     pub annotation_id: Option<ChangeAnnotationIdentifier>,
 }
 
+impl RenameFile {
+    #[must_use]
+    pub const fn new(old_uri: Uri, new_uri: Uri) -> Self {
+        Self {
+            old_uri,
+            new_uri,
+            options: None,
+            annotation_id: None,
+        }
+    }
+
+    fn options_mut(&mut self) -> &mut RenameFileOptions {
+        self.options.get_or_insert(RenameFileOptions {
+            overwrite: None,
+            ignore_if_exists: None,
+        })
+    }
+
+    /// Overwrites the target if it exists. This wins over `ignore_if_exists`.
+    #[must_use]
+    pub fn overwrite(mut self) -> Self {
+        self.options_mut().overwrite = Some(true);
+        self
+    }
+
+    /// Skips the operation if the target exists.
+    #[must_use]
+    pub fn ignore_if_exists(mut self) -> Self {
+        self.options_mut().ignore_if_exists = Some(true);
+        self
+    }
+
+    /// Describes the operation with the change annotation `id`.
+    #[must_use]
+    pub fn annotation(mut self, id: impl Into<ChangeAnnotationIdentifier>) -> Self {
+        self.annotation_id = Some(id.into());
+        self
+    }
+}
+
 /// Delete file options
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -638,6 +1330,45 @@ pub struct DeleteFile {
     pub annotation_id: Option<ChangeAnnotationIdentifier>,
 }
 
+impl DeleteFile {
+    #[must_use]
+    pub const fn new(uri: Uri) -> Self {
+        Self {
+            uri,
+            options: None,
+            annotation_id: None,
+        }
+    }
+
+    fn options_mut(&mut self) -> &mut DeleteFileOptions {
+        self.options.get_or_insert(DeleteFileOptions {
+            recursive: None,
+            ignore_if_not_exists: None,
+        })
+    }
+
+    /// Deletes the content recursively if the URI denotes a folder.
+    #[must_use]
+    pub fn recursive(mut self) -> Self {
+        self.options_mut().recursive = Some(true);
+        self
+    }
+
+    /// Skips the operation if the file does not exist.
+    #[must_use]
+    pub fn ignore_if_not_exists(mut self) -> Self {
+        self.options_mut().ignore_if_not_exists = Some(true);
+        self
+    }
+
+    /// Describes the operation with the change annotation `id`.
+    #[must_use]
+    pub fn annotation(mut self, id: impl Into<ChangeAnnotationIdentifier>) -> Self {
+        self.annotation_id = Some(id.into());
+        self
+    }
+}
+
 /// A workspace edit represents changes to many resources managed in the workspace.
 ///
 /// The edit should either provide `changes` or `documentChanges`.
@@ -733,59 +1464,460 @@ pub struct ConfigurationItem {
     pub section: Option<String>,
 }
 
-impl WorkspaceEdit {
+impl ConfigurationParams {
+    /// Creates params asking for each of `sections` in the default scope.
     #[must_use]
-    pub fn new(changes: HashMap<Uri, Vec<TextEdit>>) -> Self {
+    pub fn from_sections<I>(sections: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
         Self {
-            changes: Some(changes),
-            document_changes: None,
-            ..Default::default()
+            items: sections
+                .into_iter()
+                .map(ConfigurationItem::section)
+                .collect(),
         }
     }
-}
 
-/// Text documents are identified using a URI. On the protocol level, URIs are passed as strings.
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
-pub struct TextDocumentIdentifier {
-    // !!!!!! Note:
-    // In the spec VersionedTextDocumentIdentifier extends TextDocumentIdentifier
-    // This modelled by "mixing-in" TextDocumentIdentifier in VersionedTextDocumentIdentifier,
-    // so any changes to this type must be effected in the sub-type as well.
-    /// The text document's URI.
-    pub uri: Uri,
+    /// Deserializes the client's `workspace/configuration` response, which
+    /// holds one value per requested item, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the response doesn't have one value per item or if
+    /// a value doesn't deserialize to `T`.
+    pub fn parse_response<T: de::DeserializeOwned>(
+        &self,
+        response: &[Value],
+    ) -> Result<Vec<T>, serde_json::Error> {
+        if response.len() != self.items.len() {
+            return Err(serde_json::Error::invalid_length(
+                response.len(),
+                &format!("{} configuration values", self.items.len()).as_str(),
+            ));
+        }
+        response.iter().map(T::deserialize).collect()
+    }
 }
 
-impl TextDocumentIdentifier {
+impl ConfigurationItem {
+    /// Creates an item asking for `section` in the default scope.
     #[must_use]
-    pub const fn new(uri: Uri) -> Self {
-        Self { uri }
+    pub fn section(section: impl Into<String>) -> Self {
+        Self {
+            scope_uri: None,
+            section: Some(section.into()),
+        }
     }
-}
-
-/// An item to transfer a text document from the client to the server.
-#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct TextDocumentItem {
-    /// The text document's URI.
-    pub uri: Uri,
-
-    /// The text document's language identifier.
-    pub language_id: String,
 
-    /// The version number of this document (it will strictly increase after each
-    /// change, including undo/redo).
-    pub version: i32,
+    /// Creates an item asking for `section` as it applies to `scope_uri`.
+    #[must_use]
+    pub fn for_scope(scope_uri: Uri, section: impl Into<String>) -> Self {
+        Self::section(section).with_scope_uri(scope_uri)
+    }
 
-    /// The content of the opened text document.
-    pub text: String,
+    /// Restricts this item to the configuration applying to `scope_uri`.
+    #[must_use]
+    pub fn with_scope_uri(mut self, scope_uri: Uri) -> Self {
+        self.scope_uri = Some(scope_uri);
+        self
+    }
 }
 
-impl TextDocumentItem {
+impl WorkspaceEdit {
     #[must_use]
-    pub const fn new(uri: Uri, language_id: String, version: i32, text: String) -> Self {
+    pub fn new(changes: HashMap<Uri, Vec<TextEdit>>) -> Self {
         Self {
-            uri,
-            language_id,
+            changes: Some(changes),
+            document_changes: None,
+            ..Default::default()
+        }
+    }
+
+    /// Registers a change annotation with `label` in `change_annotations`,
+    /// and returns its identifier, which is unique within this edit.
+    pub fn add_annotation(&mut self, label: impl Into<String>) -> ChangeAnnotationIdentifier {
+        let annotations = self.change_annotations.get_or_insert_with(HashMap::new);
+        let mut n = annotations.len();
+        while annotations.contains_key(&n.to_string()) {
+            n += 1;
+        }
+        let id = n.to_string();
+        annotations.insert(
+            id.clone(),
+            ChangeAnnotation {
+                label: label.into(),
+                needs_confirmation: None,
+                description: None,
+            },
+        );
+        id
+    }
+
+    /// Adds `edit`, annotated with `annotation_id`, to the edits of
+    /// `text_document` in `document_changes`.
+    ///
+    /// The edit is appended to the edits of the same document, or to a new
+    /// `TextDocumentEdit` at the end. When `document_changes` holds resource
+    /// operations, only a trailing edit of the same document is extended, so
+    /// that the edit is still applied after the preceding operations.
+    /// `annotation_id` should come from [`WorkspaceEdit::add_annotation`].
+    pub fn annotate_edit(
+        &mut self,
+        text_document: OptionalVersionedTextDocumentIdentifier,
+        edit: TextEdit,
+        annotation_id: ChangeAnnotationIdentifier,
+    ) {
+        let edit = OneOf::Right(AnnotatedTextEdit {
+            text_edit: edit,
+            annotation_id,
+        });
+        match self
+            .document_changes
+            .get_or_insert_with(|| DocumentChanges::Edits(Vec::new()))
+        {
+            DocumentChanges::Edits(edits) => {
+                if let Some(document_edit) = edits
+                    .iter_mut()
+                    .rev()
+                    .find(|document_edit| document_edit.text_document == text_document)
+                {
+                    document_edit.edits.push(edit);
+                } else {
+                    edits.push(TextDocumentEdit {
+                        text_document,
+                        edits: vec![edit],
+                    });
+                }
+            }
+            DocumentChanges::Operations(operations) => {
+                if let Some(DocumentChangeOperation::Edit(document_edit)) = operations.last_mut()
+                    && document_edit.text_document == text_document
+                {
+                    document_edit.edits.push(edit);
+                } else {
+                    operations.push(DocumentChangeOperation::Edit(TextDocumentEdit {
+                        text_document,
+                        edits: vec![edit],
+                    }));
+                }
+            }
+        }
+    }
+
+    /// Checks that this edit only uses features the client declared in its
+    /// `workspace.workspaceEdit` capabilities.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first unsupported feature found: `documentChanges` without
+    /// client support, a resource operation missing from `resourceOperations`,
+    /// or change annotations without `changeAnnotationSupport`.
+    pub fn is_supported_by(
+        &self,
+        capabilities: &WorkspaceEditClientCapabilities,
+    ) -> Result<(), UnsupportedEdit> {
+        let mut uses_annotations = self
+            .change_annotations
+            .as_ref()
+            .is_some_and(|annotations| !annotations.is_empty());
+
+        let support = capabilities.summary();
+        if let Some(document_changes) = &self.document_changes {
+            if !support.document_changes {
+                return Err(UnsupportedEdit::DocumentChanges);
+            }
+
+            let is_annotated = |edit: &TextDocumentEdit| {
+                edit.edits
+                    .iter()
+                    .any(|edit| matches!(edit, OneOf::Right(_)))
+            };
+            match document_changes {
+                DocumentChanges::Edits(edits) => uses_annotations |= edits.iter().any(is_annotated),
+                DocumentChanges::Operations(operations) => {
+                    for operation in operations {
+                        let (kind, annotation_id) = match operation {
+                            DocumentChangeOperation::Edit(edit) => {
+                                uses_annotations |= is_annotated(edit);
+                                continue;
+                            }
+                            DocumentChangeOperation::Op(ResourceOp::Create(op)) => {
+                                (ResourceOperationKind::Create, &op.annotation_id)
+                            }
+                            DocumentChangeOperation::Op(ResourceOp::Rename(op)) => {
+                                (ResourceOperationKind::Rename, &op.annotation_id)
+                            }
+                            DocumentChangeOperation::Op(ResourceOp::Delete(op)) => {
+                                (ResourceOperationKind::Delete, &op.annotation_id)
+                            }
+                        };
+                        if !support.supports_operation(kind) {
+                            return Err(UnsupportedEdit::ResourceOperation(kind));
+                        }
+                        uses_annotations |= annotation_id.is_some();
+                    }
+                }
+            }
+        }
+
+        if uses_annotations && !support.annotations {
+            return Err(UnsupportedEdit::ChangeAnnotations);
+        }
+
+        Ok(())
+    }
+}
+
+/// A feature used by a [`WorkspaceEdit`] that the client does not support.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum UnsupportedEdit {
+    /// The edit uses `documentChanges` but the client lacks `documentChanges` support.
+    DocumentChanges,
+    /// The edit uses a resource operation missing from the client's `resourceOperations`.
+    ResourceOperation(ResourceOperationKind),
+    /// The edit uses change annotations but the client lacks `changeAnnotationSupport`.
+    ChangeAnnotations,
+}
+
+impl std::fmt::Display for UnsupportedEdit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DocumentChanges => write!(f, "client does not support document changes"),
+            Self::ResourceOperation(kind) => {
+                write!(f, "client does not support the {kind:?} resource operation")
+            }
+            Self::ChangeAnnotations => write!(f, "client does not support change annotations"),
+        }
+    }
+}
+
+impl std::error::Error for UnsupportedEdit {}
+
+/// Applies `edit` to an in-memory map from file URIs to their content, e.g.
+/// to test a rename or refactoring provider end to end.
+///
+/// Resource operations and text document edits of `document_changes` are
+/// applied in order, honoring the create, rename and delete options; `changes`
+/// is only used if there are no `document_changes`. Text edit positions are
+/// interpreted in `encoding`. The map holds no document versions, so a
+/// versioned text document edit is only accepted for a file that existed
+/// before `edit`, not one created or renamed by it.
+///
+/// On error, `files` may be partially modified.
+///
+/// # Errors
+///
+/// Returns an error if an operation or edit addresses a missing file, would
+/// overwrite an existing file, is versioned for a file created by `edit`, or
+/// if the text edits of a document overlap.
+pub fn simulate_workspace_edit<S: BuildHasher>(
+    files: &mut HashMap<Uri, String, S>,
+    edit: &WorkspaceEdit,
+    encoding: &PositionEncodingKind,
+) -> Result<(), SimError> {
+    let Some(document_changes) = &edit.document_changes else {
+        for (uri, edits) in edit.changes.iter().flatten() {
+            apply_text_edits(files, uri, edits.iter(), encoding)?;
+        }
+        return Ok(());
+    };
+
+    let operations = match document_changes {
+        DocumentChanges::Edits(edits) => {
+            for edit in edits {
+                apply_document_edit(files, &HashSet::new(), edit, encoding)?;
+            }
+            return Ok(());
+        }
+        DocumentChanges::Operations(operations) => operations,
+    };
+    let mut created = HashSet::new();
+    for operation in operations {
+        match operation {
+            DocumentChangeOperation::Edit(edit) => {
+                apply_document_edit(files, &created, edit, encoding)?;
+            }
+            DocumentChangeOperation::Op(ResourceOp::Create(op)) => {
+                let options = op.options.as_ref();
+                if files.contains_key(&op.uri) && options.and_then(|o| o.overwrite) != Some(true) {
+                    if options.and_then(|o| o.ignore_if_exists) == Some(true) {
+                        continue;
+                    }
+                    return Err(SimError::FileExists(op.uri.clone()));
+                }
+                files.insert(op.uri.clone(), String::new());
+                created.insert(op.uri.clone());
+            }
+            DocumentChangeOperation::Op(ResourceOp::Rename(op)) => {
+                let options = op.options.as_ref();
+                if !files.contains_key(&op.old_uri) {
+                    return Err(SimError::MissingFile(op.old_uri.clone()));
+                }
+                if files.contains_key(&op.new_uri)
+                    && options.and_then(|o| o.overwrite) != Some(true)
+                {
+                    if options.and_then(|o| o.ignore_if_exists) == Some(true) {
+                        continue;
+                    }
+                    return Err(SimError::FileExists(op.new_uri.clone()));
+                }
+                let content = files.remove(&op.old_uri).unwrap_or_default();
+                files.insert(op.new_uri.clone(), content);
+                created.remove(&op.old_uri);
+                created.insert(op.new_uri.clone());
+            }
+            DocumentChangeOperation::Op(ResourceOp::Delete(op)) => {
+                let options = op.options.as_ref();
+                let mut removed = files.remove(&op.uri).is_some();
+                if options.and_then(|o| o.recursive) == Some(true) {
+                    let count = files.len();
+                    files.retain(|uri, _| uri.relative_to(&op.uri).is_none());
+                    removed |= files.len() < count;
+                }
+                if !removed && options.and_then(|o| o.ignore_if_not_exists) != Some(true) {
+                    return Err(SimError::MissingFile(op.uri.clone()));
+                }
+                created.remove(&op.uri);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Applies a text document edit, checking that a versioned edit does not
+/// address a file in `created`.
+fn apply_document_edit<S: BuildHasher>(
+    files: &mut HashMap<Uri, String, S>,
+    created: &HashSet<Uri>,
+    edit: &TextDocumentEdit,
+    encoding: &PositionEncodingKind,
+) -> Result<(), SimError> {
+    let uri = &edit.text_document.uri;
+    if let Some(version) = edit.text_document.version
+        && created.contains(uri)
+    {
+        return Err(SimError::UnexpectedVersion {
+            uri: uri.clone(),
+            version,
+        });
+    }
+    let edits = edit.edits.iter().map(|edit| match edit {
+        OneOf::Left(edit) => edit,
+        OneOf::Right(edit) => &edit.text_edit,
+    });
+    apply_text_edits(files, uri, edits, encoding)
+}
+
+/// Applies non-overlapping `edits` to the content of `uri` in `files`.
+/// Insertions at the same position are applied in the order given.
+fn apply_text_edits<'a, S: BuildHasher>(
+    files: &mut HashMap<Uri, String, S>,
+    uri: &Uri,
+    edits: impl Iterator<Item = &'a TextEdit> + Clone,
+    encoding: &PositionEncodingKind,
+) -> Result<(), SimError> {
+    let text = files
+        .get_mut(uri)
+        .ok_or_else(|| SimError::MissingFile(uri.clone()))?;
+    check_overlap(edits.clone().map(|edit| edit.range)).map_err(SimError::Edit)?;
+
+    let mut spans: Vec<_> = edits
+        .map(|edit| {
+            let start = byte_offset_from_position(text, edit.range.start, encoding);
+            let end = byte_offset_from_position(text, edit.range.end, encoding);
+            (start, end.max(start), edit.new_text.as_str())
+        })
+        .collect();
+    spans.sort_by_key(|&(start, end, _)| (start, end));
+
+    let mut result = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for (start, end, new_text) in spans {
+        result.push_str(&text[cursor..start.max(cursor)]);
+        result.push_str(new_text);
+        cursor = cursor.max(end);
+    }
+    result.push_str(&text[cursor..]);
+    *text = result;
+    Ok(())
+}
+
+/// An error applying a [`WorkspaceEdit`] with [`simulate_workspace_edit`].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SimError {
+    /// An operation or edit addresses a file that does not exist.
+    MissingFile(Uri),
+    /// A create or rename operation targets an existing file without
+    /// `overwrite` or `ignoreIfExists`.
+    FileExists(Uri),
+    /// A text document edit expects a version of a file that was created
+    /// or renamed by the same workspace edit.
+    UnexpectedVersion { uri: Uri, version: i32 },
+    /// The text edits of a document are invalid.
+    Edit(EditError),
+}
+
+impl std::fmt::Display for SimError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingFile(uri) => write!(f, "file {} does not exist", uri.as_str()),
+            Self::FileExists(uri) => write!(f, "file {} already exists", uri.as_str()),
+            Self::UnexpectedVersion { uri, version } => write!(
+                f,
+                "edit expects version {version} of {}, which was created by the same edit",
+                uri.as_str()
+            ),
+            Self::Edit(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for SimError {}
+
+/// Text documents are identified using a URI. On the protocol level, URIs are passed as strings.
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+pub struct TextDocumentIdentifier {
+    // !!!!!! Note:
+    // In the spec VersionedTextDocumentIdentifier extends TextDocumentIdentifier
+    // This modelled by "mixing-in" TextDocumentIdentifier in VersionedTextDocumentIdentifier,
+    // so any changes to this type must be effected in the sub-type as well.
+    /// The text document's URI.
+    pub uri: Uri,
+}
+
+impl TextDocumentIdentifier {
+    #[must_use]
+    pub const fn new(uri: Uri) -> Self {
+        Self { uri }
+    }
+}
+
+/// An item to transfer a text document from the client to the server.
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentItem {
+    /// The text document's URI.
+    pub uri: Uri,
+
+    /// The text document's language identifier.
+    pub language_id: String,
+
+    /// The version number of this document (it will strictly increase after each
+    /// change, including undo/redo).
+    pub version: i32,
+
+    /// The content of the opened text document.
+    pub text: String,
+}
+
+impl TextDocumentItem {
+    #[must_use]
+    pub const fn new(uri: Uri, language_id: String, version: i32, text: String) -> Self {
+        Self {
+            uri,
+            language_id,
             version,
             text,
         }
@@ -867,6 +1999,69 @@ impl TextDocumentPositionParams {
     }
 }
 
+/// Targets the start of the location, e.g. to follow up on a goto result.
+impl From<&Location> for TextDocumentPositionParams {
+    fn from(from: &Location) -> Self {
+        Self::new(
+            TextDocumentIdentifier::new(from.uri.clone()),
+            from.range.start,
+        )
+    }
+}
+
+/// Request params targeting a position in a text document, i.e. those
+/// extending `TextDocumentPositionParams`, so that generic code such as logging
+/// or routing can read the target location uniformly.
+pub trait HasTextDocumentPosition {
+    /// The text document and position fields.
+    fn text_document_position(&self) -> &TextDocumentPositionParams;
+
+    /// The URI of the text document.
+    fn uri(&self) -> &Uri {
+        &self.text_document_position().text_document.uri
+    }
+
+    /// The position inside the text document.
+    fn position(&self) -> Position {
+        self.text_document_position().position
+    }
+}
+
+impl HasTextDocumentPosition for TextDocumentPositionParams {
+    fn text_document_position(&self) -> &TextDocumentPositionParams {
+        self
+    }
+}
+
+macro_rules! has_text_document_position {
+    ($($params:ty => $field:ident),* $(,)?) => {
+        $(
+            impl HasTextDocumentPosition for $params {
+                fn text_document_position(&self) -> &TextDocumentPositionParams {
+                    &self.$field
+                }
+            }
+        )*
+    };
+}
+
+has_text_document_position!(
+    CallHierarchyPrepareParams => text_document_position_params,
+    CompletionParams => text_document_position,
+    DocumentHighlightParams => text_document_position_params,
+    DocumentOnTypeFormattingParams => text_document_position,
+    GenericParams => text_document_position_params,
+    GotoDefinitionParams => text_document_position_params,
+    HoverParams => text_document_position_params,
+    InlineCompletionParams => text_document_position,
+    LinkedEditingRangeParams => text_document_position_params,
+    MonikerParams => text_document_position_params,
+    ReferenceParams => text_document_position,
+    RenameParams => text_document_position,
+    SignatureHelpParams => text_document_position_params,
+    TypeHierarchyPrepareParams => text_document_position_params,
+);
+
 /// A document filter denotes a document through properties like language, schema or pattern.
 ///
 /// Examples are a filter that applies to TypeScript files on disk or a filter the applies to JSON
@@ -955,6 +2150,86 @@ pub struct InitializeParams {
     pub work_done_progress_params: WorkDoneProgressParams,
 }
 
+impl InitializeParams {
+    /// Parses the client's `locale` into a [`LanguageTag`].
+    ///
+    /// Returns `None` if no locale was sent or if it is not a well-formed tag.
+    #[must_use]
+    pub fn language_tag(&self) -> Option<LanguageTag> {
+        self.locale.as_deref().and_then(LanguageTag::parse)
+    }
+
+    /// Deserializes the server-specific `initialization_options` into a typed
+    /// struct.
+    ///
+    /// Returns `None` if the client sent no options, or the result of
+    /// deserializing them otherwise.
+    #[must_use]
+    pub fn initialization_options_as<T: de::DeserializeOwned>(
+        &self,
+    ) -> Option<Result<T, serde_json::Error>> {
+        self.initialization_options.as_ref().map(T::deserialize)
+    }
+}
+
+/// The primary language and region subtags of an IETF language tag, e.g.
+/// `en` and `US` for `en-US`.
+///
+/// Other subtags (script, variants, extensions) are validated but discarded.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct LanguageTag {
+    language: String,
+    region: Option<String>,
+}
+
+impl LanguageTag {
+    /// Parses an IETF language tag such as `en`, `en-US` or `zh-Hant-TW`.
+    ///
+    /// The language is normalized to lowercase and the region to uppercase.
+    #[must_use]
+    pub fn parse(tag: &str) -> Option<Self> {
+        let mut subtags = tag.split('-');
+        let language = subtags.next()?;
+        if !(2..=8).contains(&language.len()) || !language.bytes().all(|b| b.is_ascii_alphabetic())
+        {
+            return None;
+        }
+
+        let mut region = None;
+        let mut in_extension = false;
+        for subtag in subtags {
+            if !(1..=8).contains(&subtag.len())
+                || !subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+            {
+                return None;
+            }
+            in_extension |= subtag.len() == 1;
+            let is_region = (subtag.len() == 2 && subtag.bytes().all(|b| b.is_ascii_alphabetic()))
+                || (subtag.len() == 3 && subtag.bytes().all(|b| b.is_ascii_digit()));
+            if is_region && region.is_none() && !in_extension {
+                region = Some(subtag.to_ascii_uppercase());
+            }
+        }
+
+        Some(Self {
+            language: language.to_ascii_lowercase(),
+            region,
+        })
+    }
+
+    /// The primary language subtag, e.g. `en`.
+    #[must_use]
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// The region subtag, e.g. `US`.
+    #[must_use]
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct ClientInfo {
     /// The name of the client as defined by the client.
@@ -1049,6 +2324,51 @@ pub struct WorkspaceEditClientCapabilities {
     pub change_annotation_support: Option<ChangeAnnotationWorkspaceEditClientCapabilities>,
 }
 
+impl WorkspaceEditClientCapabilities {
+    /// Resolves these capabilities into plain flags, treating unset ones as
+    /// unsupported.
+    #[must_use]
+    pub fn summary(&self) -> WorkspaceEditSupport {
+        let operations = self.resource_operations.as_deref().unwrap_or(&[]);
+        WorkspaceEditSupport {
+            document_changes: self.document_changes == Some(true),
+            can_create: operations.contains(&ResourceOperationKind::Create),
+            can_rename: operations.contains(&ResourceOperationKind::Rename),
+            can_delete: operations.contains(&ResourceOperationKind::Delete),
+            annotations: self.change_annotation_support.is_some(),
+        }
+    }
+}
+
+/// The [`WorkspaceEdit`] features a client supports, as resolved by
+/// [`WorkspaceEditClientCapabilities::summary`].
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+#[expect(clippy::struct_excessive_bools)]
+pub struct WorkspaceEditSupport {
+    /// Versioned `documentChanges` are supported.
+    pub document_changes: bool,
+    /// The `create` resource operation is supported.
+    pub can_create: bool,
+    /// The `rename` resource operation is supported.
+    pub can_rename: bool,
+    /// The `delete` resource operation is supported.
+    pub can_delete: bool,
+    /// Change annotations are supported.
+    pub annotations: bool,
+}
+
+impl WorkspaceEditSupport {
+    /// Whether the given resource operation is supported.
+    #[must_use]
+    pub const fn supports_operation(&self, kind: ResourceOperationKind) -> bool {
+        match kind {
+            ResourceOperationKind::Create => self.can_create,
+            ResourceOperationKind::Rename => self.can_rename,
+            ResourceOperationKind::Delete => self.can_delete,
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize, Copy, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum ResourceOperationKind {
@@ -1535,6 +2855,24 @@ pub struct RegularExpressionsClientCapabilities {
     pub version: Option<String>,
 }
 
+impl RegularExpressionsClientCapabilities {
+    /// The engine name used by clients implementing ECMAScript regular expressions.
+    pub const ECMASCRIPT: &'static str = "ECMAScript";
+
+    /// Returns whether the client's regular expression engine is `name`,
+    /// compared ASCII case-insensitively.
+    #[must_use]
+    pub fn engine_matches(&self, name: &str) -> bool {
+        self.engine.eq_ignore_ascii_case(name)
+    }
+
+    /// Returns whether the client uses an ECMAScript regular expression engine.
+    #[must_use]
+    pub fn is_ecmascript(&self) -> bool {
+        self.engine_matches(Self::ECMASCRIPT)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarkdownClientCapabilities {
@@ -1553,6 +2891,155 @@ pub struct MarkdownClientCapabilities {
     pub allowed_tags: Option<Vec<String>>,
 }
 
+impl MarkdownClientCapabilities {
+    /// Returns whether the client allows the given HTML tag in Markdown.
+    ///
+    /// Tag names are compared ASCII case-insensitively. If the client did not
+    /// send `allowed_tags`, no tag is considered allowed.
+    #[must_use]
+    pub fn allows_tag(&self, tag: &str) -> bool {
+        self.allowed_tags
+            .as_ref()
+            .is_some_and(|tags| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+
+    /// Strips the HTML tags of `markdown` that are not in the client's
+    /// `allowed_tags`, keeping the text in between.
+    ///
+    /// Only opening, closing and self-closing tags are recognized; anything
+    /// else starting with `<` (comments, `a < b`, ...) is left untouched, as
+    /// are code spans and fenced code blocks, so that e.g. `Vec<T>` survives.
+    /// The content of a disallowed `script` or `style` element is dropped
+    /// along with its tags.
+    #[must_use]
+    pub fn sanitize_markdown(&self, markdown: &str) -> String {
+        let mut out = String::with_capacity(markdown.len());
+        let mut fence = None;
+        let mut text_start = 0;
+        let mut offset = 0;
+        for line in markdown.split_inclusive('\n') {
+            let line_start = offset;
+            offset += line.len();
+            match fence {
+                None => {
+                    if let Some(open) = code_fence(line) {
+                        self.sanitize_text(&markdown[text_start..line_start], &mut out);
+                        out.push_str(line);
+                        fence = Some(open);
+                    }
+                }
+                Some((marker, len)) => {
+                    out.push_str(line);
+                    let closes = code_fence(line).is_some_and(|(c, n)| {
+                        c == marker
+                            && n >= len
+                            && line
+                                .trim_start_matches(' ')
+                                .trim_start_matches(c)
+                                .trim()
+                                .is_empty()
+                    });
+                    if closes {
+                        fence = None;
+                        text_start = offset;
+                    }
+                }
+            }
+        }
+        if fence.is_none() {
+            self.sanitize_text(&markdown[text_start..], &mut out);
+        }
+        out
+    }
+
+    /// Sanitizes Markdown text outside of fenced code blocks into `out`.
+    fn sanitize_text(&self, text: &str, out: &mut String) {
+        let mut rest = text;
+        while let Some(start) = rest.find(['<', '`']) {
+            out.push_str(&rest[..start]);
+            let candidate = &rest[start..];
+            if candidate.starts_with('`') {
+                let len = code_span_len(candidate);
+                out.push_str(&candidate[..len]);
+                rest = &candidate[len..];
+            } else if let Some((name, len)) = html_tag(candidate) {
+                let tag = &candidate[..len];
+                rest = &candidate[len..];
+                if self.allows_tag(name) {
+                    out.push_str(tag);
+                } else if ["script", "style"]
+                    .iter()
+                    .any(|raw| raw.eq_ignore_ascii_case(name))
+                    && !tag.starts_with("</")
+                    && !tag.ends_with("/>")
+                {
+                    rest = &rest[raw_text_len(rest, name)..];
+                }
+            } else {
+                out.push('<');
+                rest = &candidate[1..];
+            }
+        }
+        out.push_str(rest);
+    }
+}
+
+/// Parses the opening or closing line of a fenced code block, returning its
+/// marker character and length.
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let line = line.trim_start_matches(' ');
+    let marker = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = line.len() - line.trim_start_matches(marker).len();
+    (len >= 3).then_some((marker, len))
+}
+
+/// The byte length of the code span at the start of `s`, or of its opening
+/// backticks if the span is never closed.
+fn code_span_len(s: &str) -> usize {
+    let backticks = |s: &str| s.len() - s.trim_start_matches('`').len();
+    let open = backticks(s);
+    let mut pos = open;
+    while let Some(next) = s[pos..].find('`') {
+        let start = pos + next;
+        let len = backticks(&s[start..]);
+        if len == open {
+            return start + len;
+        }
+        pos = start + len;
+    }
+    open
+}
+
+/// The byte length of the content of raw text element `name` at the start
+/// of `s`, including its closing tag, or of all of `s` if it is not closed.
+fn raw_text_len(s: &str, name: &str) -> usize {
+    let closing = format!("</{}", name.to_ascii_lowercase());
+    s.to_ascii_lowercase()
+        .find(&closing)
+        .and_then(|start| s[start..].find('>').map(|end| start + end + 1))
+        .unwrap_or(s.len())
+}
+
+/// Parses an HTML tag at the start of `s`, returning its name and byte length.
+fn html_tag(s: &str) -> Option<(&str, usize)> {
+    let inner = s.strip_prefix('<')?;
+    let inner = inner.strip_prefix('/').unwrap_or(inner);
+    let name_len = inner
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(inner.len());
+    let name = &inner[..name_len];
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let after_name = &inner[name_len..];
+    if !after_name.starts_with(['>', '/', ' ', '\t', '\n', '\r']) {
+        return None;
+    }
+    let end = after_name.find('>')?;
+    let len = s.len() - after_name.len() + end + 1;
+    Some((name, len))
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeResult {
@@ -1673,6 +3160,35 @@ pub struct TextDocumentSyncOptions {
     pub save: Option<TextDocumentSyncSaveOptions>,
 }
 
+impl TextDocumentSyncOptions {
+    /// Open and close notifications with full document content on change.
+    #[must_use]
+    pub fn full() -> Self {
+        Self::with_change(TextDocumentSyncKind::FULL)
+    }
+
+    /// Open and close notifications with incremental updates on change.
+    #[must_use]
+    pub fn incremental() -> Self {
+        Self::with_change(TextDocumentSyncKind::INCREMENTAL)
+    }
+
+    fn with_change(change: TextDocumentSyncKind) -> Self {
+        Self {
+            open_close: Some(true),
+            change: Some(change),
+            ..Self::default()
+        }
+    }
+}
+
+/// Either an `A` or a `B`, (de)serialized without any tag.
+///
+/// Deserialization tries `Left` first and falls back to `Right`. If `A` is a
+/// structural subset of `B` (every valid `B` is also a valid `A`), `Right` is
+/// never produced and the extra fields of `B` are silently dropped: such
+/// fields need a dedicated deserializer trying `B` first (see
+/// `TextDocumentEdit::edits`).
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum OneOf<A, B> {
@@ -1680,6 +3196,88 @@ pub enum OneOf<A, B> {
     Right(B),
 }
 
+/// Capability fields of the form `OneOf<bool, Options>` accept a plain `bool`,
+/// so `Some(true.into())` enables a provider with default options.
+impl<B> From<bool> for OneOf<bool, B> {
+    fn from(from: bool) -> Self {
+        Self::Left(from)
+    }
+}
+
+// A blanket `From<B> for OneOf<A, B>` would overlap with `From<bool>` above,
+// so the right-hand conversions are spelled out per capability options type.
+macro_rules! one_of_bool_from_options {
+    ($($options:ty),* $(,)?) => {
+        $(
+            impl From<$options> for OneOf<bool, $options> {
+                fn from(from: $options) -> Self {
+                    Self::Right(from)
+                }
+            }
+        )*
+    };
+}
+
+one_of_bool_from_options!(
+    DefinitionOptions,
+    DocumentFormattingOptions,
+    DocumentHighlightOptions,
+    DocumentRangeFormattingOptions,
+    DocumentSymbolOptions,
+    InlayHintServerCapabilities,
+    InlineCompletionOptions,
+    InlineValueServerCapabilities,
+    MonikerServerCapabilities,
+    ReferenceOptions,
+    RenameOptions,
+    WorkspaceSymbolOptions,
+);
+
+/// A server capability that enables a provider, either with a plain `bool` or
+/// with options.
+pub trait ProviderCapability {
+    /// Whether the provider is enabled, i.e. not the plain value `false`.
+    fn is_enabled(&self) -> bool;
+}
+
+impl<T> ProviderCapability for OneOf<bool, T> {
+    fn is_enabled(&self) -> bool {
+        !matches!(self, Self::Left(false))
+    }
+}
+
+/// An unset provider is disabled.
+impl<P: ProviderCapability> ProviderCapability for Option<P> {
+    fn is_enabled(&self) -> bool {
+        self.as_ref().is_some_and(ProviderCapability::is_enabled)
+    }
+}
+
+macro_rules! simple_provider_capability {
+    ($($capability:ty),* $(,)?) => {
+        $(
+            impl ProviderCapability for $capability {
+                fn is_enabled(&self) -> bool {
+                    !matches!(self, Self::Simple(false))
+                }
+            }
+        )*
+    };
+}
+
+simple_provider_capability!(
+    CallHierarchyServerCapability,
+    CodeActionProviderCapability,
+    ColorProviderCapability,
+    DeclarationCapability,
+    FoldingRangeProviderCapability,
+    HoverProviderCapability,
+    ImplementationProviderCapability,
+    LinkedEditingRangeServerCapabilities,
+    SelectionRangeProviderCapability,
+    TypeDefinitionProviderCapability,
+);
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum TextDocumentSyncCapability {
@@ -1687,6 +3285,18 @@ pub enum TextDocumentSyncCapability {
     Options(TextDocumentSyncOptions),
 }
 
+impl TextDocumentSyncCapability {
+    /// The change kind of either form, defaulting to `TextDocumentSyncKind::NONE`
+    /// when the options omit `change`.
+    #[must_use]
+    pub fn change_kind(&self) -> TextDocumentSyncKind {
+        match self {
+            Self::Kind(kind) => *kind,
+            Self::Options(options) => options.change.unwrap_or(TextDocumentSyncKind::NONE),
+        }
+    }
+}
+
 impl From<TextDocumentSyncOptions> for TextDocumentSyncCapability {
     fn from(from: TextDocumentSyncOptions) -> Self {
         Self::Options(from)
@@ -1902,6 +3512,284 @@ pub struct ServerCapabilities {
     pub experimental: Option<Value>,
 }
 
+impl ServerCapabilities {
+    /// The kind of `textDocument/didChange` notifications the server expects,
+    /// whichever form `text_document_sync` takes. Defaults to
+    /// `TextDocumentSyncKind::NONE` when omitted.
+    #[must_use]
+    pub fn effective_sync_kind(&self) -> TextDocumentSyncKind {
+        self.text_document_sync.as_ref().map_or(
+            TextDocumentSyncKind::NONE,
+            TextDocumentSyncCapability::change_kind,
+        )
+    }
+
+    /// Whether the server handles requests of `method`, e.g.
+    /// `textDocument/hover`, according to the corresponding provider.
+    ///
+    /// A provider is enabled if it is set and not `false`. Resolve requests
+    /// also need `resolveProvider`, and `textDocument/prepareRename`, the
+    /// semantic tokens requests and `workspace/diagnostic` their respective
+    /// options. Unknown methods, and methods without a provider such as
+    /// lifecycle requests, are reported as not provided.
+    #[must_use]
+    #[expect(clippy::too_many_lines)]
+    pub fn provides(&self, method: &str) -> bool {
+        let semantic_tokens =
+            self.semantic_tokens_provider
+                .as_ref()
+                .map(|capability| match capability {
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+                    SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
+                        options,
+                    ) => &options.semantic_tokens_options,
+                });
+        let diagnostics = self
+            .diagnostic_provider
+            .as_ref()
+            .map(|capability| match capability {
+                DiagnosticServerCapabilities::Options(options) => options,
+                DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                    &options.diagnostic_options
+                }
+            });
+
+        match method {
+            "textDocument/selectionRange" => self.selection_range_provider.is_enabled(),
+            "textDocument/hover" => self.hover_provider.is_enabled(),
+            "textDocument/completion" => self.completion_provider.is_some(),
+            "completionItem/resolve" => self
+                .completion_provider
+                .as_ref()
+                .is_some_and(|options| options.resolve_provider == Some(true)),
+            "textDocument/signatureHelp" => self.signature_help_provider.is_some(),
+            "textDocument/definition" => self.definition_provider.is_enabled(),
+            "textDocument/typeDefinition" => self.type_definition_provider.is_enabled(),
+            "textDocument/implementation" => self.implementation_provider.is_enabled(),
+            "textDocument/references" => self.references_provider.is_enabled(),
+            "textDocument/documentHighlight" => self.document_highlight_provider.is_enabled(),
+            "textDocument/documentSymbol" => self.document_symbol_provider.is_enabled(),
+            "workspace/symbol" => self.workspace_symbol_provider.is_enabled(),
+            "workspaceSymbol/resolve" => matches!(
+                &self.workspace_symbol_provider,
+                Some(OneOf::Right(options)) if options.resolve_provider == Some(true)
+            ),
+            "textDocument/codeAction" => self.code_action_provider.is_enabled(),
+            "codeAction/resolve" => matches!(
+                &self.code_action_provider,
+                Some(CodeActionProviderCapability::Options(options))
+                    if options.resolve_provider == Some(true)
+            ),
+            "textDocument/codeLens" => self.code_lens_provider.is_some(),
+            "codeLens/resolve" => self
+                .code_lens_provider
+                .as_ref()
+                .is_some_and(|options| options.resolve_provider == Some(true)),
+            "textDocument/formatting" => self.document_formatting_provider.is_enabled(),
+            "textDocument/rangeFormatting" => self.document_range_formatting_provider.is_enabled(),
+            "textDocument/onTypeFormatting" => self.document_on_type_formatting_provider.is_some(),
+            "textDocument/rename" => self.rename_provider.is_enabled(),
+            "textDocument/prepareRename" => matches!(
+                &self.rename_provider,
+                Some(OneOf::Right(options)) if options.prepare_provider == Some(true)
+            ),
+            "textDocument/documentLink" => self.document_link_provider.is_some(),
+            "documentLink/resolve" => self
+                .document_link_provider
+                .as_ref()
+                .is_some_and(|options| options.resolve_provider == Some(true)),
+            "textDocument/documentColor" | "textDocument/colorPresentation" => {
+                self.color_provider.is_enabled()
+            }
+            "textDocument/foldingRange" => self.folding_range_provider.is_enabled(),
+            "textDocument/declaration" => self.declaration_provider.is_enabled(),
+            "workspace/executeCommand" => self.execute_command_provider.is_some(),
+            "textDocument/prepareCallHierarchy"
+            | "callHierarchy/incomingCalls"
+            | "callHierarchy/outgoingCalls" => self.call_hierarchy_provider.is_enabled(),
+            "textDocument/semanticTokens/full" => semantic_tokens.is_some_and(|options| {
+                !matches!(
+                    options.full,
+                    None | Some(SemanticTokensFullOptions::Bool(false))
+                )
+            }),
+            "textDocument/semanticTokens/full/delta" => semantic_tokens.is_some_and(|options| {
+                matches!(
+                    options.full,
+                    Some(SemanticTokensFullOptions::Delta { delta: Some(true) })
+                )
+            }),
+            "textDocument/semanticTokens/range" => {
+                semantic_tokens.is_some_and(|options| options.range == Some(true))
+            }
+            "textDocument/moniker" => self.moniker_provider.is_enabled(),
+            "textDocument/linkedEditingRange" => self.linked_editing_range_provider.is_enabled(),
+            "textDocument/inlineValue" => self.inline_value_provider.is_enabled(),
+            "textDocument/inlayHint" => self.inlay_hint_provider.is_enabled(),
+            "inlayHint/resolve" => match &self.inlay_hint_provider {
+                Some(OneOf::Right(InlayHintServerCapabilities::Options(options))) => {
+                    options.resolve_provider == Some(true)
+                }
+                Some(OneOf::Right(InlayHintServerCapabilities::RegistrationOptions(options))) => {
+                    options.inlay_hint_options.resolve_provider == Some(true)
+                }
+                _ => false,
+            },
+            "textDocument/diagnostic" => diagnostics.is_some(),
+            "workspace/diagnostic" => {
+                diagnostics.is_some_and(|options| options.workspace_diagnostics)
+            }
+            "textDocument/inlineCompletion" => self.inline_completion_provider.is_enabled(),
+            _ => false,
+        }
+    }
+
+    /// Merges the capabilities of `other` into `self`, e.g. to advertise the
+    /// union of several servers behind a router.
+    ///
+    /// A provider is enabled if either side enables it: a provider disabled
+    /// or unset in `self` is replaced by the one of `other`, otherwise the
+    /// options of `self` take precedence. Where both sides have options, the
+    /// trigger and commit characters of completion, signature help and
+    /// on-type formatting, as well as the executable commands, are unioned,
+    /// and `resolveProvider` is set if either side sets it. Any other
+    /// capability, such as `text_document_sync`, is only taken from `other`
+    /// when unset in `self`.
+    pub fn merge(&mut self, other: &Self) {
+        if let (Some(this), Some(other)) =
+            (&mut self.completion_provider, &other.completion_provider)
+        {
+            merge_flag(&mut this.resolve_provider, other.resolve_provider);
+            union_strings(
+                &mut this.trigger_characters,
+                other.trigger_characters.as_deref(),
+            );
+            union_strings(
+                &mut this.all_commit_characters,
+                other.all_commit_characters.as_deref(),
+            );
+        }
+        if let (Some(this), Some(other)) = (
+            &mut self.signature_help_provider,
+            &other.signature_help_provider,
+        ) {
+            union_strings(
+                &mut this.trigger_characters,
+                other.trigger_characters.as_deref(),
+            );
+            union_strings(
+                &mut this.retrigger_characters,
+                other.retrigger_characters.as_deref(),
+            );
+        }
+        if let (Some(this), Some(other)) = (&mut self.code_lens_provider, &other.code_lens_provider)
+        {
+            merge_flag(&mut this.resolve_provider, other.resolve_provider);
+        }
+        if let (Some(this), Some(other)) = (
+            &mut self.document_on_type_formatting_provider,
+            &other.document_on_type_formatting_provider,
+        ) {
+            let characters = std::iter::once(&other.first_trigger_character)
+                .chain(other.more_trigger_character.iter().flatten())
+                .filter(|character| **character != this.first_trigger_character)
+                .cloned()
+                .collect::<Vec<_>>();
+            union_strings(&mut this.more_trigger_character, Some(&characters));
+        }
+        if let (Some(this), Some(other)) = (
+            &mut self.document_link_provider,
+            &other.document_link_provider,
+        ) {
+            merge_flag(&mut this.resolve_provider, other.resolve_provider);
+        }
+        if let (Some(this), Some(other)) = (
+            &mut self.execute_command_provider,
+            &other.execute_command_provider,
+        ) {
+            let mut commands = Some(std::mem::take(&mut this.commands));
+            union_strings(&mut commands, Some(&other.commands));
+            this.commands = commands.unwrap_or_default();
+        }
+
+        macro_rules! merge {
+            ($merge:ident: $($field:ident),* $(,)?) => {
+                $($merge(&mut self.$field, other.$field.as_ref());)*
+            };
+        }
+        merge!(
+            merge_option: position_encoding,
+            text_document_sync,
+            notebook_document_sync,
+            completion_provider,
+            signature_help_provider,
+            code_lens_provider,
+            document_on_type_formatting_provider,
+            document_link_provider,
+            execute_command_provider,
+            workspace,
+            semantic_tokens_provider,
+            diagnostic_provider,
+            experimental,
+        );
+        merge!(
+            merge_provider: selection_range_provider,
+            hover_provider,
+            definition_provider,
+            type_definition_provider,
+            implementation_provider,
+            references_provider,
+            document_highlight_provider,
+            document_symbol_provider,
+            workspace_symbol_provider,
+            code_action_provider,
+            document_formatting_provider,
+            document_range_formatting_provider,
+            rename_provider,
+            color_provider,
+            folding_range_provider,
+            declaration_provider,
+            call_hierarchy_provider,
+            moniker_provider,
+            linked_editing_range_provider,
+            inline_value_provider,
+            inlay_hint_provider,
+            inline_completion_provider,
+        );
+    }
+}
+
+/// Sets `this` to `other` if `this` is unset.
+fn merge_option<T: Clone>(this: &mut Option<T>, other: Option<&T>) {
+    if this.is_none() {
+        *this = other.cloned();
+    }
+}
+
+/// Sets `this` to `other` if `this` is unset or disabled and `other` is enabled.
+fn merge_provider<P: ProviderCapability + Clone>(this: &mut Option<P>, other: Option<&P>) {
+    if !this.is_enabled() && other.is_some_and(ProviderCapability::is_enabled) {
+        *this = other.cloned();
+    }
+}
+
+/// Sets `this` to `Some(true)` if `other` is.
+fn merge_flag(this: &mut Option<bool>, other: Option<bool>) {
+    if other == Some(true) {
+        *this = Some(true);
+    }
+}
+
+/// Appends the strings of `other` missing from `this`.
+fn union_strings(this: &mut Option<Vec<String>>, other: Option<&[String]>) {
+    for string in other.unwrap_or_default() {
+        let this = this.get_or_insert_with(Vec::new);
+        if !this.contains(string) {
+            this.push(string.clone());
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceServerCapabilities {
@@ -1934,6 +3822,82 @@ pub struct RegistrationParams {
     pub registrations: Vec<Registration>,
 }
 
+/// Whether the client supports registering `method` dynamically, according
+/// to the `dynamicRegistration` flag of the corresponding capability, so
+/// that servers can choose between a static capability and a
+/// `client/registerCapability` request.
+///
+/// `method` is the method of the [`Registration`], e.g. `textDocument/hover`
+/// or `textDocument/semanticTokens`. Unknown methods, and methods the
+/// protocol offers no dynamic registration for, are reported as not
+/// supported.
+#[must_use]
+pub fn prefers_dynamic_registration(caps: &ClientCapabilities, method: &str) -> bool {
+    let text_document = caps.text_document.as_ref();
+    let workspace = caps.workspace.as_ref();
+    macro_rules! flag {
+        ($capabilities:ident . $field:ident) => {
+            $capabilities
+                .and_then(|capabilities| capabilities.$field.as_ref())
+                .and_then(|capability| capability.dynamic_registration)
+        };
+    }
+
+    let flag = match method {
+        "textDocument/didOpen"
+        | "textDocument/didChange"
+        | "textDocument/didClose"
+        | "textDocument/willSave"
+        | "textDocument/willSaveWaitUntil"
+        | "textDocument/didSave" => flag!(text_document.synchronization),
+        "textDocument/completion" => flag!(text_document.completion),
+        "textDocument/hover" => flag!(text_document.hover),
+        "textDocument/signatureHelp" => flag!(text_document.signature_help),
+        "textDocument/references" => flag!(text_document.references),
+        "textDocument/documentHighlight" => flag!(text_document.document_highlight),
+        "textDocument/documentSymbol" => flag!(text_document.document_symbol),
+        "textDocument/formatting" => flag!(text_document.formatting),
+        "textDocument/rangeFormatting" => flag!(text_document.range_formatting),
+        "textDocument/onTypeFormatting" => flag!(text_document.on_type_formatting),
+        "textDocument/declaration" => flag!(text_document.declaration),
+        "textDocument/definition" => flag!(text_document.definition),
+        "textDocument/typeDefinition" => flag!(text_document.type_definition),
+        "textDocument/implementation" => flag!(text_document.implementation),
+        "textDocument/codeAction" => flag!(text_document.code_action),
+        "textDocument/codeLens" => flag!(text_document.code_lens),
+        "textDocument/documentLink" => flag!(text_document.document_link),
+        "textDocument/documentColor" => flag!(text_document.color_provider),
+        "textDocument/rename" => flag!(text_document.rename),
+        "textDocument/foldingRange" => flag!(text_document.folding_range),
+        "textDocument/selectionRange" => flag!(text_document.selection_range),
+        "textDocument/linkedEditingRange" => flag!(text_document.linked_editing_range),
+        "textDocument/prepareCallHierarchy" => flag!(text_document.call_hierarchy),
+        "textDocument/semanticTokens" => flag!(text_document.semantic_tokens),
+        "textDocument/moniker" => flag!(text_document.moniker),
+        "textDocument/prepareTypeHierarchy" => flag!(text_document.type_hierarchy),
+        "textDocument/inlineValue" => flag!(text_document.inline_value),
+        "textDocument/inlayHint" => flag!(text_document.inlay_hint),
+        "textDocument/diagnostic" => flag!(text_document.diagnostic),
+        "textDocument/inlineCompletion" => flag!(text_document.inline_completion),
+        "notebookDocument/sync" => caps
+            .notebook_document
+            .as_ref()
+            .and_then(|capabilities| capabilities.synchronization.dynamic_registration),
+        "workspace/didChangeConfiguration" => flag!(workspace.did_change_configuration),
+        "workspace/didChangeWatchedFiles" => flag!(workspace.did_change_watched_files),
+        "workspace/symbol" => flag!(workspace.symbol),
+        "workspace/executeCommand" => flag!(workspace.execute_command),
+        "workspace/willCreateFiles"
+        | "workspace/didCreateFiles"
+        | "workspace/willRenameFiles"
+        | "workspace/didRenameFiles"
+        | "workspace/willDeleteFiles"
+        | "workspace/didDeleteFiles" => flag!(workspace.file_operations),
+        _ => None,
+    };
+    flag == Some(true)
+}
+
 /// Since most of the registration options require to specify a document selector there is a base
 /// interface that can be used.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
@@ -1944,6 +3908,41 @@ pub struct TextDocumentRegistrationOptions {
     pub document_selector: Option<DocumentSelector>,
 }
 
+impl TextDocumentRegistrationOptions {
+    /// Creates options selecting the documents of any of `languages`.
+    #[must_use]
+    pub fn for_languages<I>(languages: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let selector = languages
+            .into_iter()
+            .map(|language| DocumentFilter {
+                language: Some(language.into()),
+                scheme: None,
+                pattern: None,
+            })
+            .collect();
+        Self {
+            document_selector: Some(selector),
+        }
+    }
+
+    /// Creates options selecting the documents with a URI of `scheme`, like
+    /// `file` or `untitled`.
+    #[must_use]
+    pub fn for_scheme(scheme: impl Into<String>) -> Self {
+        Self {
+            document_selector: Some(vec![DocumentFilter {
+                language: None,
+                scheme: Some(scheme.into()),
+                pattern: None,
+            }]),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum DeclarationCapability {
@@ -2084,6 +4083,17 @@ pub struct DidChangeConfigurationParams {
     pub settings: Value,
 }
 
+impl DidChangeConfigurationParams {
+    /// Deserializes the changed settings into a typed configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the settings don't deserialize to `T`.
+    pub fn settings_as<T: de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.settings)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DidOpenTextDocumentParams {
@@ -2121,6 +4131,16 @@ pub struct TextDocumentContentChangeEvent {
     pub text: String,
 }
 
+impl TextDocumentContentChangeEvent {
+    /// The equivalent edit of an incremental change, or `None` if the event
+    /// replaces the full content of the document.
+    #[must_use]
+    pub fn as_text_edit(&self) -> Option<TextEdit> {
+        self.range
+            .map(|range| TextEdit::new(range, self.text.clone()))
+    }
+}
+
 /// Describe options to be used when registering for text document change events.
 ///
 /// Extends `TextDocumentRegistrationOptions`
@@ -2258,6 +4278,23 @@ pub struct DidChangeWatchedFilesRegistrationOptions {
     pub watchers: Vec<FileSystemWatcher>,
 }
 
+impl DidChangeWatchedFilesRegistrationOptions {
+    /// Creates one watcher per glob pattern, each interested in the default
+    /// create, change and delete events.
+    #[must_use]
+    pub fn from_globs(patterns: &[&str]) -> Self {
+        Self {
+            watchers: patterns
+                .iter()
+                .map(|pattern| FileSystemWatcher {
+                    glob_pattern: GlobPattern::String((*pattern).to_string()),
+                    kind: None,
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileSystemWatcher {
@@ -2301,6 +4338,9 @@ impl From<RelativePattern> for GlobPattern {
 /// relatively to a base URI. The common value for a `baseUri` is a workspace
 /// folder root, but it can be another absolute URI as well.
 ///
+/// Patterns are ordered by base, then by glob. Bases relative to a
+/// [`WorkspaceFolder`] (compared by URI, then name) sort before plain URIs.
+///
 /// @since 3.17.0
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -2393,6 +4433,123 @@ pub enum Documentation {
     MarkupContent(MarkupContent),
 }
 
+/// Adapts `doc` to a client that may only render plain text, e.g. for hover,
+/// completion or signature help documentation.
+///
+/// If `client_supports_markdown` is false, markdown content is converted to
+/// plain text by stripping its syntax: heading markers, block quotes, code
+/// fences, emphasis, inline code and link targets. Anything else is returned
+/// as is.
+#[must_use]
+pub fn downgrade_documentation(
+    doc: &Documentation,
+    client_supports_markdown: bool,
+) -> Documentation {
+    match doc {
+        Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }) if !client_supports_markdown => Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::PlainText,
+            value: markdown_to_plaintext(value),
+        }),
+        _ => doc.clone(),
+    }
+}
+
+/// Strips the markdown syntax of `markdown`, keeping code blocks verbatim.
+fn markdown_to_plaintext(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) && trimmed.trim_start_matches(open).trim().is_empty() {
+                fence = None;
+            } else {
+                lines.push(line.to_string());
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = Some(marker);
+            continue;
+        }
+
+        let mut text = trimmed;
+        while let Some(quoted) = text.strip_prefix('>') {
+            text = quoted.trim_start();
+        }
+        let level = text.bytes().take_while(|&b| b == b'#').count();
+        if (1..=6).contains(&level) && text[level..].chars().next().is_none_or(char::is_whitespace)
+        {
+            text = text[level..].trim().trim_end_matches('#').trim_end();
+        }
+        lines.push(strip_inline_markdown(text));
+    }
+    lines.join("\n")
+}
+
+/// Strips emphasis, inline code, links and escapes from a line of markdown.
+fn strip_inline_markdown(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        let after = &rest[c.len_utf8()..];
+        match c {
+            '\\' if after.starts_with(|c: char| c.is_ascii_punctuation()) => {
+                out.push_str(&after[..1]);
+                rest = &after[1..];
+                continue;
+            }
+            '`' => {
+                let ticks = rest.len() - rest.trim_start_matches('`').len();
+                let code = &rest[ticks..];
+                if let Some(end) = code.find(&rest[..ticks]) {
+                    out.push_str(code[..end].trim());
+                    rest = &code[end + ticks..];
+                } else {
+                    out.push_str(&rest[..ticks]);
+                    rest = code;
+                }
+                continue;
+            }
+            '~' if after.starts_with('~') => {
+                rest = &after[1..];
+                continue;
+            }
+            '*' => {
+                let spaced = out.chars().next_back().is_some_and(char::is_whitespace)
+                    && after.starts_with(char::is_whitespace);
+                if spaced {
+                    out.push('*');
+                }
+            }
+            '_' => {
+                let inside_word = out.chars().next_back().is_some_and(char::is_alphanumeric)
+                    && after.starts_with(char::is_alphanumeric);
+                if inside_word {
+                    out.push('_');
+                }
+            }
+            '!' if after.starts_with('[') => {}
+            '[' => {
+                if let Some((text, url_and_rest)) = after.split_once("](")
+                    && let Some(url_end) = url_and_rest.find(')')
+                {
+                    out.push_str(&strip_inline_markdown(text));
+                    rest = &url_and_rest[url_end + 1..];
+                    continue;
+                }
+                out.push('[');
+            }
+            c => out.push(c),
+        }
+        rest = after;
+    }
+    out
+}
+
 /// `MarkedString` can be used to render human readable text. It is either a
 /// markdown string or a code-block that provides a language and a code snippet.
 /// The language identifier is semantically equal to the optional language
@@ -2506,6 +4663,21 @@ pub struct ApplyWorkspaceEditParams {
     pub edit: WorkspaceEdit,
 }
 
+impl ApplyWorkspaceEditParams {
+    /// Creates params asking the client to apply `edit`, without a label.
+    #[must_use]
+    pub const fn new(edit: WorkspaceEdit) -> Self {
+        Self { label: None, edit }
+    }
+
+    /// Sets the label presented for the edit, e.g. on the undo stack.
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ApplyWorkspaceEditResponse {
@@ -2579,6 +4751,16 @@ pub struct PartialResultParams {
     pub partial_result_token: Option<ProgressToken>,
 }
 
+impl PartialResultParams {
+    /// Creates params requesting partial results to be reported with `token`.
+    #[must_use]
+    pub const fn with_token(token: ProgressToken) -> Self {
+        Self {
+            partial_result_token: Some(token),
+        }
+    }
+}
+
 /// Symbol tags are extra annotations that tweak the rendering of a symbol.
 ///
 /// @since 3.16.0
@@ -2617,6 +4799,856 @@ mod tests {
         assert_eq!(&value, expected);
     }
 
+    #[test]
+    fn one_of_from_capability_values() {
+        let capabilities = ServerCapabilities {
+            definition_provider: Some(true.into()),
+            rename_provider: Some(
+                RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }
+                .into(),
+            ),
+            ..ServerCapabilities::default()
+        };
+        assert_eq!(capabilities.definition_provider, Some(OneOf::Left(true)));
+        assert!(matches!(
+            capabilities.rename_provider,
+            Some(OneOf::Right(RenameOptions {
+                prepare_provider: Some(true),
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn relative_pattern_ordering() {
+        let uri = |s: &str| s.parse::<Uri>().unwrap();
+        let folder = |s: &str, name: &str| {
+            OneOf::Left(WorkspaceFolder {
+                uri: uri(s),
+                name: name.to_string(),
+            })
+        };
+        let pattern = |base_uri, pattern: &str| RelativePattern {
+            base_uri,
+            pattern: pattern.to_string(),
+        };
+
+        let mut patterns = vec![
+            pattern(OneOf::Right(uri("file:///a")), "*.rs"),
+            pattern(folder("file:///b", "b"), "*.rs"),
+            pattern(folder("file:///a", "z"), "*.rs"),
+            pattern(folder("file:///a", "a"), "*.toml"),
+            pattern(folder("file:///a", "a"), "*.rs"),
+        ];
+        patterns.sort();
+        assert_eq!(
+            patterns,
+            vec![
+                pattern(folder("file:///a", "a"), "*.rs"),
+                pattern(folder("file:///a", "a"), "*.toml"),
+                pattern(folder("file:///a", "z"), "*.rs"),
+                pattern(folder("file:///b", "b"), "*.rs"),
+                pattern(OneOf::Right(uri("file:///a")), "*.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_json_lenient_strips_bom() {
+        let json = br#"{"capabilities":{"hoverProvider":true},"serverInfo":{"name":"ls"}}"#;
+        let expected = InitializeResult {
+            capabilities: ServerCapabilities {
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            server_info: Some(ServerInfo {
+                name: "ls".into(),
+                version: None,
+            }),
+            offset_encoding: None,
+        };
+
+        let with_bom = [b"\xEF\xBB\xBF".as_slice(), json].concat();
+        assert_eq!(
+            from_json_lenient::<InitializeResult>(&with_bom).unwrap(),
+            expected
+        );
+        assert_eq!(
+            from_json_lenient::<InitializeResult>(json).unwrap(),
+            expected
+        );
+        assert!(serde_json::from_slice::<InitializeResult>(&with_bom).is_err());
+    }
+
+    #[test]
+    fn position_encoding_kind_predicates() {
+        assert_eq!(PositionEncodingKind::default(), PositionEncodingKind::UTF16);
+        assert!(PositionEncodingKind::default().is_utf16());
+
+        let utf8 = PositionEncodingKind::from("utf-8".to_string());
+        assert!(utf8.is_utf8());
+        assert!(!utf8.is_utf16());
+        assert!(!utf8.is_utf32());
+        assert!(PositionEncodingKind::UTF32.is_utf32());
+
+        let other = PositionEncodingKind::new("utf-7");
+        assert!(!other.is_utf8() && !other.is_utf16() && !other.is_utf32());
+    }
+
+    #[test]
+    fn diff_text_edits_minimal() {
+        let utf16 = &PositionEncodingKind::UTF16;
+        let range = |sl, sc, el, ec| Range::new(Position::new(sl, sc), Position::new(el, ec));
+        let apply = |old: &str, edits: Vec<TextEdit>| {
+            let uri: Uri = "file:///a.rs".parse().unwrap();
+            let mut files = HashMap::from([(uri.clone(), old.to_string())]);
+            simulate_workspace_edit(
+                &mut files,
+                &WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)])),
+                utf16,
+            )
+            .unwrap();
+            files.remove(&uri).unwrap()
+        };
+
+        let old = "fn main() {\n    let x = 1;\n}\n";
+        let new = "fn main() {\n    let x = 42;\n}\n";
+        let edits = diff_text_edits(old, new, utf16);
+        assert_eq!(edits, vec![TextEdit::new(range(1, 12, 1, 13), "42".into())]);
+        assert_eq!(apply(old, edits), new);
+
+        let new = "fn main() {\n    let x = 1;\n    dbg!(x);\n}\n";
+        let edits = diff_text_edits(old, new, utf16);
+        assert_eq!(
+            edits,
+            vec![TextEdit::new(range(2, 0, 2, 0), "    dbg!(x);\n".into())]
+        );
+        assert_eq!(apply(old, edits), new);
+
+        let old = "a\r\nb\r\nc\r\nd";
+        let new = "a\r\nB\r\nc\r\nd\r\né";
+        let edits = diff_text_edits(old, new, utf16);
+        assert_eq!(
+            edits,
+            vec![
+                TextEdit::new(range(1, 0, 1, 1), "B".into()),
+                TextEdit::new(range(3, 1, 3, 1), "\r\né".into()),
+            ]
+        );
+        assert_eq!(apply(old, edits), new);
+
+        assert!(diff_text_edits(old, old, utf16).is_empty());
+        assert_eq!(apply("", diff_text_edits("", new, utf16)), new);
+        assert_eq!(apply(new, diff_text_edits(new, "", utf16)), "");
+    }
+
+    #[test]
+    fn diff_text_edits_large() {
+        let utf16 = &PositionEncodingKind::UTF16;
+        let lines = |prefix: &str| {
+            (0..3000)
+                .map(|i| format!("{prefix} {i}\n"))
+                .collect::<Vec<_>>()
+                .concat()
+        };
+        let (old, new) = (
+            format!("head\n{}tail\n", lines("old")),
+            format!("head\n{}tail\n", lines("new")),
+        );
+
+        let edits = diff_text_edits(&old, &new, utf16);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.start, Position::new(1, 0));
+        assert_eq!(edits[0].range.end, Position::new(3000, 3));
+
+        let mut document = old;
+        let start = byte_offset_from_position(&document, edits[0].range.start, utf16);
+        let end = byte_offset_from_position(&document, edits[0].range.end, utf16);
+        document.replace_range(start..end, &edits[0].new_text);
+        assert_eq!(document, new);
+    }
+
+    #[test]
+    fn byte_offset_position_conversion() {
+        // "é" is 2 UTF-8 bytes and one UTF-16 unit, "𝕏" is 4 UTF-8 bytes and two UTF-16 units.
+        let text = "aé𝕏b\r\n𝕏\nc";
+        let cases = [
+            (0, (0, 0), (0, 0), (0, 0)),
+            (3, (0, 3), (0, 2), (0, 2)),
+            (7, (0, 7), (0, 4), (0, 3)),
+            (8, (0, 8), (0, 5), (0, 4)),
+            (10, (1, 0), (1, 0), (1, 0)),
+            (14, (1, 4), (1, 2), (1, 1)),
+            (15, (2, 0), (2, 0), (2, 0)),
+            (16, (2, 1), (2, 1), (2, 1)),
+        ];
+        for (offset, utf8, utf16, utf32) in cases {
+            for (encoding, (line, character)) in [
+                (PositionEncodingKind::UTF8, utf8),
+                (PositionEncodingKind::UTF16, utf16),
+                (PositionEncodingKind::UTF32, utf32),
+            ] {
+                let position = Position::new(line, character);
+                assert_eq!(
+                    position_from_byte_offset(text, offset, &encoding),
+                    position,
+                    "{offset} in {encoding:?}"
+                );
+                assert_eq!(
+                    byte_offset_from_position(text, position, &encoding),
+                    offset,
+                    "{position:?} in {encoding:?}"
+                );
+            }
+        }
+
+        let utf16 = &PositionEncodingKind::UTF16;
+        // Inside "𝕏", inside "\r\n" and past the end clamp back.
+        assert_eq!(
+            position_from_byte_offset(text, 5, utf16),
+            Position::new(0, 2)
+        );
+        assert_eq!(
+            position_from_byte_offset(text, 9, utf16),
+            Position::new(0, 5)
+        );
+        assert_eq!(
+            position_from_byte_offset(text, 99, utf16),
+            Position::new(2, 1)
+        );
+        // Between surrogate halves, past the line end and past the last line.
+        assert_eq!(
+            byte_offset_from_position(text, Position::new(0, 3), utf16),
+            3
+        );
+        assert_eq!(
+            byte_offset_from_position(text, Position::new(0, 9), utf16),
+            8
+        );
+        assert_eq!(
+            byte_offset_from_position(text, Position::new(5, 0), utf16),
+            16
+        );
+    }
+
+    #[test]
+    fn byte_span_range_conversion() {
+        let text = "fn é() {\r\n    𝕏\r\n}\r\n";
+        let body = text.find('{').unwrap()..text.rfind('}').unwrap() + 1;
+        assert_eq!(
+            range_from_byte_span(text, body.clone(), &PositionEncodingKind::UTF16),
+            Range::new(Position::new(0, 7), Position::new(2, 1))
+        );
+        assert_eq!(
+            range_from_byte_span(text, body, &PositionEncodingKind::UTF8),
+            Range::new(Position::new(0, 8), Position::new(2, 1))
+        );
+
+        let second_line = text.find("    ").unwrap()..text.find('}').unwrap();
+        assert_eq!(
+            range_from_byte_span(text, second_line, &PositionEncodingKind::UTF32),
+            Range::new(Position::new(1, 0), Position::new(2, 0))
+        );
+    }
+
+    #[test]
+    fn downgrade_markdown_documentation() {
+        let markdown = |value: &str| {
+            Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: value.to_string(),
+            })
+        };
+        let plaintext = |value: &str| {
+            Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::PlainText,
+                value: value.to_string(),
+            })
+        };
+
+        let doc = markdown(
+            "# Header #\n## `foo_bar` **function**\n> see [docs](https://example.com)\n\n```rust\nfn *foo*() {}\n```\n~~~\n# not a header\n~~~\n*a* * _b_ \\*c\\* ~~d~~",
+        );
+        assert_eq!(
+            downgrade_documentation(&doc, false),
+            plaintext(
+                "Header\nfoo_bar function\nsee docs\n\nfn *foo*() {}\n# not a header\na * b *c* d"
+            )
+        );
+        assert_eq!(downgrade_documentation(&doc, true), doc);
+
+        let plain = Documentation::String("**kept**".into());
+        assert_eq!(downgrade_documentation(&plain, false), plain);
+    }
+
+    #[test]
+    fn simulate_workspace_edit_create_and_edit() {
+        let main: Uri = "file:///src/main.rs".parse().unwrap();
+        let util: Uri = "file:///src/util.rs".parse().unwrap();
+        let range =
+            |line, start, end| Range::new(Position::new(line, start), Position::new(line, end));
+        let text_edit = |uri: &Uri, version, edits: Vec<TextEdit>| {
+            DocumentChangeOperation::Edit(TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier {
+                    uri: uri.clone(),
+                    version,
+                },
+                edits: edits.into_iter().map(OneOf::Left).collect(),
+            })
+        };
+        let create = DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+            uri: util.clone(),
+            options: None,
+            annotation_id: None,
+        }));
+
+        let mut files =
+            HashMap::from([(main.clone(), "fn main() {\n    helper();\n}\n".to_string())]);
+        let edit = WorkspaceEdit {
+            document_changes: Some(DocumentChanges::Operations(vec![
+                create.clone(),
+                text_edit(
+                    &util,
+                    None,
+                    vec![TextEdit::new(range(0, 0, 0), "pub fn helper() {}\n".into())],
+                ),
+                text_edit(
+                    &main,
+                    Some(3),
+                    vec![
+                        TextEdit::new(range(1, 4, 4), "util::".into()),
+                        TextEdit::new(range(0, 0, 0), "mod util;\n".into()),
+                        TextEdit::new(range(0, 0, 0), "\n".into()),
+                    ],
+                ),
+            ])),
+            ..Default::default()
+        };
+        simulate_workspace_edit(&mut files, &edit, &PositionEncodingKind::UTF16).unwrap();
+        assert_eq!(
+            files,
+            HashMap::from([
+                (
+                    main,
+                    "mod util;\n\nfn main() {\n    util::helper();\n}\n".to_string()
+                ),
+                (util.clone(), "pub fn helper() {}\n".to_string()),
+            ])
+        );
+
+        // A versioned edit cannot address a file created by the same edit.
+        let mut files = HashMap::new();
+        assert_eq!(
+            simulate_workspace_edit(
+                &mut files,
+                &WorkspaceEdit {
+                    document_changes: Some(DocumentChanges::Operations(vec![
+                        create,
+                        text_edit(&util, Some(1), vec![]),
+                    ])),
+                    ..Default::default()
+                },
+                &PositionEncodingKind::UTF16
+            ),
+            Err(SimError::UnexpectedVersion {
+                uri: util,
+                version: 1
+            })
+        );
+    }
+
+    #[test]
+    fn number_or_string_matches_id() {
+        let number = NumberOrString::Number(42);
+        let string = NumberOrString::String("42".into());
+
+        assert!(number.matches_id(&NumberOrString::Number(42)));
+        assert!(!number.matches_id(&NumberOrString::Number(43)));
+        assert!(string.matches_id(&NumberOrString::String("42".into())));
+        assert!(!string.matches_id(&NumberOrString::String("abc".into())));
+        assert!(number.matches_id(&string));
+        assert!(string.matches_id(&number));
+        assert!(!number.matches_id(&NumberOrString::String("042".into())));
+
+        let cancel: CancelParams = serde_json::from_str(r#"{"id":"42"}"#).unwrap();
+        assert!(cancel.id.matches_id(&number));
+    }
+
+    #[test]
+    fn position_negative_values() {
+        test_deserialization(
+            r#"{"start":{"line":1,"character":0},"end":{"line":2,"character":4}}"#,
+            &Range::new(Position::new(1, 0), Position::new(2, 4)),
+        );
+        assert!(serde_json::from_str::<Position>(r#"{"line":-1,"character":0}"#).is_err());
+        assert!(serde_json::from_str::<Position>(r#"{"line":0,"character":-3}"#).is_err());
+        assert!(
+            serde_json::from_str::<Range>(
+                r#"{"start":{"line":-1,"character":0},"end":{"line":0,"character":0}}"#
+            )
+            .is_err()
+        );
+        assert!(serde_json::from_str::<Position>(r#"{"line":4294967296,"character":0}"#).is_err());
+
+        assert_eq!(Position::try_from_signed(3, 7), Some(Position::new(3, 7)));
+        assert_eq!(Position::try_from_signed(-1, 7), None);
+        assert_eq!(Position::try_from_signed(3, -7), None);
+        assert_eq!(Position::try_from_signed(i64::from(u32::MAX) + 1, 0), None);
+    }
+
+    #[test]
+    fn position_ordering_predicates() {
+        let a = Position::new(1, 5);
+        let b = Position::new(2, 0);
+
+        assert!(a.is_before(&b));
+        assert!(!b.is_before(&a));
+        assert!(!a.is_before(&a));
+        assert!(b.is_after(&a));
+        assert!(!a.is_after(&a));
+
+        assert!(Position::new(1, 9).is_between(&a, &b));
+        assert!(a.is_between(&a, &b));
+        assert!(b.is_between(&a, &b));
+        assert!(!Position::new(1, 4).is_between(&a, &b));
+        assert!(!Position::new(2, 1).is_between(&a, &b));
+    }
+
+    #[test]
+    fn text_document_registration_options_builders() {
+        test_serialization(
+            &TextDocumentRegistrationOptions::for_languages(["rust", "toml"]),
+            r#"{"documentSelector":[{"language":"rust"},{"language":"toml"}]}"#,
+        );
+        test_serialization(
+            &TextDocumentRegistrationOptions::for_scheme("file"),
+            r#"{"documentSelector":[{"scheme":"file"}]}"#,
+        );
+    }
+
+    #[test]
+    fn workspace_edit_annotations() {
+        let document = OptionalVersionedTextDocumentIdentifier {
+            uri: "file:///a.rs".parse().unwrap(),
+            version: Some(3),
+        };
+        let range = |line| Range::new(Position::new(line, 0), Position::new(line, 3));
+
+        let mut edit = WorkspaceEdit::default();
+        let rename = edit.add_annotation("Rename foo");
+        let import = edit.add_annotation("Add import");
+        assert_ne!(rename, import);
+
+        edit.annotate_edit(
+            document.clone(),
+            TextEdit::new(range(1), "bar".into()),
+            rename.clone(),
+        );
+        edit.annotate_edit(
+            document,
+            TextEdit::new(range(0), "use".into()),
+            import.clone(),
+        );
+
+        let annotations = edit.change_annotations.as_ref().unwrap();
+        assert_eq!(annotations[&rename].label, "Rename foo");
+        assert_eq!(annotations[&import].label, "Add import");
+        let Some(DocumentChanges::Edits(edits)) = &edit.document_changes else {
+            panic!("expected text document edits");
+        };
+        assert_eq!(edits.len(), 1);
+        assert_eq!(
+            edits[0].edits,
+            vec![
+                OneOf::Right(AnnotatedTextEdit {
+                    text_edit: TextEdit::new(range(1), "bar".into()),
+                    annotation_id: rename,
+                }),
+                OneOf::Right(AnnotatedTextEdit {
+                    text_edit: TextEdit::new(range(0), "use".into()),
+                    annotation_id: import,
+                }),
+            ]
+        );
+
+        let capabilities = WorkspaceEditClientCapabilities {
+            document_changes: Some(true),
+            change_annotation_support: Some(
+                ChangeAnnotationWorkspaceEditClientCapabilities::default(),
+            ),
+            ..Default::default()
+        };
+        assert_eq!(edit.is_supported_by(&capabilities), Ok(()));
+    }
+
+    #[test]
+    fn resource_operation_builders() {
+        let a: Uri = "file:///a.rs".parse().unwrap();
+        let b: Uri = "file:///b.rs".parse().unwrap();
+
+        test_serialization(&CreateFile::new(a.clone()), r#"{"uri":"file:///a.rs"}"#);
+        test_serialization(
+            &CreateFile::new(a.clone()).overwrite().annotation("gen"),
+            r#"{"uri":"file:///a.rs","options":{"overwrite":true},"annotationId":"gen"}"#,
+        );
+        test_serialization(
+            &RenameFile::new(a.clone(), b).ignore_if_exists(),
+            r#"{"oldUri":"file:///a.rs","newUri":"file:///b.rs","options":{"ignoreIfExists":true}}"#,
+        );
+        test_serialization(
+            &DeleteFile::new(a)
+                .recursive()
+                .ignore_if_not_exists()
+                .annotation("cleanup"),
+            r#"{"uri":"file:///a.rs","options":{"recursive":true,"ignoreIfNotExists":true},"annotationId":"cleanup"}"#,
+        );
+    }
+
+    #[test]
+    fn apply_workspace_edit_params() {
+        use request::{ApplyWorkspaceEdit, Request};
+
+        assert_eq!(ApplyWorkspaceEdit::METHOD, "workspace/applyEdit");
+        let uri: Uri = "file:///a.rs".parse().unwrap();
+        let edit = WorkspaceEdit::new(HashMap::from([(
+            uri,
+            vec![TextEdit::new(Range::default(), "use std::fmt;\n".into())],
+        )]));
+
+        test_serialization(
+            &ApplyWorkspaceEditParams::new(WorkspaceEdit::default()),
+            r#"{"edit":{}}"#,
+        );
+        test_serialization(
+            &ApplyWorkspaceEditParams::new(edit).with_label("Add import"),
+            r#"{"label":"Add import","edit":{"changes":{"file:///a.rs":[{"range":{"start":{"line":0,"character":0},"end":{"line":0,"character":0}},"newText":"use std::fmt;\n"}]}}}"#,
+        );
+    }
+
+    #[test]
+    fn text_document_position_from_location() {
+        let location = Location::new(
+            "file:///a.rs".parse().unwrap(),
+            Range::new(Position::new(4, 2), Position::new(4, 9)),
+        );
+        let params = TextDocumentPositionParams::from(&location);
+        assert_eq!(params.text_document.uri, location.uri);
+        assert_eq!(params.position, Position::new(4, 2));
+    }
+
+    #[test]
+    fn has_text_document_position() {
+        fn target(params: &impl HasTextDocumentPosition) -> (&str, Position) {
+            (params.uri().as_str(), params.position())
+        }
+
+        let position = TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new("file:///a.rs".parse().unwrap()),
+            Position::new(3, 7),
+        );
+        let hover = HoverParams {
+            text_document_position_params: position.clone(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+        let rename = RenameParams {
+            text_document_position: position,
+            new_name: "b".to_string(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+
+        assert_eq!(target(&hover), ("file:///a.rs", Position::new(3, 7)));
+        assert_eq!(target(&rename), ("file:///a.rs", Position::new(3, 7)));
+    }
+
+    #[test]
+    fn content_change_event_as_text_edit() {
+        let range = Range::new(Position::new(1, 2), Position::new(1, 5));
+        let incremental = TextDocumentContentChangeEvent {
+            range: Some(range),
+            range_length: None,
+            text: "foo".to_string(),
+        };
+        assert_eq!(
+            incremental.as_text_edit(),
+            Some(TextEdit::new(range, "foo".to_string()))
+        );
+
+        let full = TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "new content".to_string(),
+        };
+        assert_eq!(full.as_text_edit(), None);
+    }
+
+    #[test]
+    fn provider_capability_is_enabled() {
+        assert!(!None::<OneOf<bool, RenameOptions>>.is_enabled());
+        assert!(!Some(OneOf::<bool, RenameOptions>::Left(false)).is_enabled());
+        assert!(Some(OneOf::<bool, RenameOptions>::Left(true)).is_enabled());
+        assert!(
+            Some(OneOf::<bool, _>::Right(MonikerServerCapabilities::Options(
+                MonikerOptions {
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }
+            )))
+            .is_enabled()
+        );
+
+        assert!(!HoverProviderCapability::Simple(false).is_enabled());
+        assert!(HoverProviderCapability::Simple(true).is_enabled());
+        assert!(HoverProviderCapability::Options(HoverOptions::default()).is_enabled());
+        assert!(!None::<DeclarationCapability>.is_enabled());
+    }
+
+    #[test]
+    fn server_capabilities_provides() {
+        let mut capabilities = ServerCapabilities {
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            rename_provider: Some(OneOf::Left(false)),
+            completion_provider: Some(CompletionOptions::default()),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(false)),
+            ..Default::default()
+        };
+
+        assert!(capabilities.provides("textDocument/hover"));
+        assert!(!capabilities.provides("textDocument/rename"));
+        assert!(!capabilities.provides("textDocument/prepareRename"));
+        assert!(capabilities.provides("textDocument/completion"));
+        assert!(!capabilities.provides("completionItem/resolve"));
+        assert!(!capabilities.provides("textDocument/codeAction"));
+        assert!(!capabilities.provides("textDocument/definition"));
+        assert!(!capabilities.provides("initialize"));
+        assert!(!capabilities.provides("textDocument/unknown"));
+
+        capabilities.rename_provider = Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        }));
+        assert!(capabilities.provides("textDocument/rename"));
+        assert!(capabilities.provides("textDocument/prepareRename"));
+
+        capabilities.inlay_hint_provider = Some(OneOf::Left(true));
+        capabilities.workspace_symbol_provider = Some(OneOf::Left(true));
+        assert!(capabilities.provides("textDocument/inlayHint"));
+        assert!(!capabilities.provides("inlayHint/resolve"));
+        assert!(capabilities.provides("workspace/symbol"));
+        assert!(!capabilities.provides("workspaceSymbol/resolve"));
+
+        capabilities.inlay_hint_provider = Some(OneOf::Right(
+            InlayHintServerCapabilities::Options(InlayHintOptions {
+                resolve_provider: Some(true),
+                ..Default::default()
+            }),
+        ));
+        capabilities.workspace_symbol_provider = Some(OneOf::Right(WorkspaceSymbolOptions {
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+            resolve_provider: Some(true),
+        }));
+        assert!(capabilities.provides("inlayHint/resolve"));
+        assert!(capabilities.provides("workspaceSymbol/resolve"));
+    }
+
+    #[test]
+    fn server_capabilities_merge() {
+        let mut capabilities = ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncKind::FULL.into()),
+            hover_provider: Some(HoverProviderCapability::Simple(false)),
+            rename_provider: Some(OneOf::Left(true)),
+            completion_provider: Some(CompletionOptions {
+                trigger_characters: Some(vec![".".to_string()]),
+                ..Default::default()
+            }),
+            execute_command_provider: Some(ExecuteCommandOptions {
+                commands: vec!["a.fix".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        capabilities.merge(&ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncKind::INCREMENTAL.into()),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            rename_provider: Some(OneOf::Left(false)),
+            definition_provider: Some(OneOf::Left(true)),
+            completion_provider: Some(CompletionOptions {
+                resolve_provider: Some(true),
+                trigger_characters: Some(vec![":".to_string(), ".".to_string()]),
+                ..Default::default()
+            }),
+            execute_command_provider: Some(ExecuteCommandOptions {
+                commands: vec!["b.run".to_string(), "a.fix".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            capabilities.effective_sync_kind(),
+            TextDocumentSyncKind::FULL
+        );
+        assert!(capabilities.provides("textDocument/hover"));
+        assert!(capabilities.provides("textDocument/rename"));
+        assert!(capabilities.provides("textDocument/definition"));
+        assert!(capabilities.provides("completionItem/resolve"));
+        assert!(!capabilities.provides("textDocument/references"));
+        assert_eq!(
+            capabilities.completion_provider.unwrap().trigger_characters,
+            Some(vec![".".to_string(), ":".to_string()])
+        );
+        assert_eq!(
+            capabilities.execute_command_provider.unwrap().commands,
+            ["a.fix", "b.run"]
+        );
+
+        let mut capabilities = ServerCapabilities {
+            document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions::new("}")),
+            ..Default::default()
+        };
+        capabilities.merge(&ServerCapabilities {
+            document_on_type_formatting_provider: Some(
+                DocumentOnTypeFormattingOptions::new("\n").more_trigger_characters(["}", ";"]),
+            ),
+            signature_help_provider: Some(SignatureHelpOptions {
+                trigger_characters: Some(vec!["(".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        test_serialization(
+            &capabilities,
+            r#"{"signatureHelpProvider":{"triggerCharacters":["("]},"documentOnTypeFormattingProvider":{"firstTriggerCharacter":"}","moreTriggerCharacter":["\n",";"]}}"#,
+        );
+    }
+
+    #[test]
+    fn dynamic_registration_preference() {
+        let caps: ClientCapabilities = serde_json::from_str(
+            r#"{
+                "textDocument": {
+                    "hover": {"dynamicRegistration": true},
+                    "completion": {"dynamicRegistration": false},
+                    "synchronization": {"dynamicRegistration": true}
+                },
+                "workspace": {"didChangeWatchedFiles": {"dynamicRegistration": true}}
+            }"#,
+        )
+        .unwrap();
+
+        assert!(prefers_dynamic_registration(&caps, "textDocument/hover"));
+        assert!(prefers_dynamic_registration(&caps, "textDocument/didSave"));
+        assert!(prefers_dynamic_registration(
+            &caps,
+            "workspace/didChangeWatchedFiles"
+        ));
+        assert!(!prefers_dynamic_registration(
+            &caps,
+            "textDocument/completion"
+        ));
+        assert!(!prefers_dynamic_registration(&caps, "textDocument/rename"));
+        assert!(!prefers_dynamic_registration(&caps, "workspace/symbol"));
+        assert!(!prefers_dynamic_registration(&caps, "initialize"));
+        assert!(!prefers_dynamic_registration(
+            &ClientCapabilities::default(),
+            "textDocument/hover"
+        ));
+    }
+
+    #[test]
+    fn text_edit_is_noop() {
+        let empty = Range::new(Position::new(1, 2), Position::new(1, 2));
+        let word = Range::new(Position::new(1, 2), Position::new(1, 5));
+
+        assert!(TextEdit::new(empty, String::new()).is_noop(""));
+        assert!(TextEdit::new(word, "foo".to_string()).is_noop("foo"));
+        assert!(!TextEdit::new(empty, "foo".to_string()).is_noop(""));
+        assert!(!TextEdit::new(word, String::new()).is_noop("foo"));
+        assert!(!TextEdit::new(word, "bar".to_string()).is_noop("foo"));
+    }
+
+    #[test]
+    fn text_edit_normalize_line_endings() {
+        let normalized = |text: &str, eol: &str| {
+            let mut edit = TextEdit::new(Range::default(), text.to_string());
+            edit.normalize_line_endings(eol);
+            edit.new_text
+        };
+
+        assert_eq!(normalized("a\r\nb\r\n", "\n"), "a\nb\n");
+        assert_eq!(normalized("a\nb\n", "\r\n"), "a\r\nb\r\n");
+        assert_eq!(normalized("a\rb\r", "\n"), "a\nb\n");
+        assert_eq!(normalized("a\r\nb\nc\rd", "\r\n"), "a\r\nb\r\nc\r\nd");
+        assert_eq!(normalized("a\n\r\n\r", "\r"), "a\r\r\r");
+        assert_eq!(normalized("no line ending", "\r\n"), "no line ending");
+    }
+
+    #[test]
+    fn typed_configuration_settings() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Settings {
+            check: CheckSettings,
+            max_problems: u32,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct CheckSettings {
+            command: String,
+            extra_args: Vec<String>,
+        }
+
+        let params: DidChangeConfigurationParams = serde_json::from_str(
+            r#"{"settings":{"check":{"command":"clippy","extra_args":["--all"]},"maxProblems":10}}"#,
+        )
+        .unwrap();
+        let expected = Settings {
+            check: CheckSettings {
+                command: "clippy".to_string(),
+                extra_args: vec!["--all".to_string()],
+            },
+            max_problems: 10,
+        };
+        assert_eq!(params.settings_as::<Settings>().unwrap(), expected);
+        assert!(params.settings_as::<CheckSettings>().is_err());
+
+        let request = ConfigurationParams {
+            items: vec![
+                ConfigurationItem::section("check")
+                    .with_scope_uri("file:///project".parse().unwrap()),
+            ],
+        };
+        test_serialization(
+            &request,
+            r#"{"items":[{"scopeUri":"file:///project","section":"check"}]}"#,
+        );
+        let response = [serde_json::json!({"command": "clippy", "extra_args": []})];
+        assert_eq!(
+            request.parse_response::<CheckSettings>(&response).unwrap(),
+            vec![CheckSettings {
+                command: "clippy".to_string(),
+                extra_args: vec![],
+            }]
+        );
+        assert_eq!(
+            request
+                .parse_response::<CheckSettings>(&[])
+                .unwrap_err()
+                .to_string(),
+            "invalid length 0, expected 1 configuration values"
+        );
+
+        test_serialization(
+            &ConfigurationParams::from_sections(["check", "maxProblems"]),
+            r#"{"items":[{"section":"check"},{"section":"maxProblems"}]}"#,
+        );
+        assert_eq!(
+            ConfigurationItem::for_scope("file:///project".parse().unwrap(), "check"),
+            request.items[0]
+        );
+    }
+
     #[test]
     fn one_of() {
         test_serialization(&OneOf::<bool, ()>::Left(true), r"true");
@@ -2636,6 +5668,13 @@ mod tests {
         test_serialization(&NumberOrString::String("abcd".into()), r#""abcd""#);
     }
 
+    #[test]
+    fn number_or_string_large_number() {
+        test_deserialization("5000000000", &NumberOrString::Number(5_000_000_000));
+        test_serialization(&NumberOrString::Number(5_000_000_000), r"5000000000");
+        assert_eq!(NumberOrString::from(42_i32), NumberOrString::Number(42));
+    }
+
     #[test]
     fn marked_string() {
         test_serialization(&MarkedString::from_markdown("xxx".into()), r#""xxx""#);
@@ -2691,6 +5730,393 @@ mod tests {
         );
     }
 
+    #[test]
+    fn markdown_sanitization() {
+        let capabilities = MarkdownClientCapabilities {
+            parser: "marked".into(),
+            version: None,
+            allowed_tags: Some(vec!["b".into()]),
+        };
+        assert_eq!(
+            capabilities.sanitize_markdown("<b>bold</b> <script>alert(1)</script>"),
+            "<b>bold</b> "
+        );
+        assert_eq!(
+            capabilities
+                .sanitize_markdown("a<SCRIPT type=\"x\">\nlet b = 1 < 2;\n</script >c<style/>d"),
+            "acd"
+        );
+        assert_eq!(
+            capabilities.sanitize_markdown("`HashMap<K, V>` or ``Vec<`T`>``, <i>not</i> `Vec<T>"),
+            "`HashMap<K, V>` or ``Vec<`T`>``, not `Vec"
+        );
+        assert_eq!(
+            capabilities.sanitize_markdown(
+                "<i>x</i>\n```rust\nlet v: Vec<T>;\n````\n~~~\nOption<u8>\n~~~\n<br>"
+            ),
+            "x\n```rust\nlet v: Vec<T>;\n````\n~~~\nOption<u8>\n~~~\n"
+        );
+        assert_eq!(
+            capabilities.sanitize_markdown("<B class=\"x\">a</B><br/> 1 < 2"),
+            "<B class=\"x\">a</B> 1 < 2"
+        );
+
+        let capabilities = MarkdownClientCapabilities::default();
+        assert_eq!(capabilities.sanitize_markdown("<b>bold</b>"), "bold");
+    }
+
+    #[test]
+    fn regular_expressions_engine() {
+        let capabilities = RegularExpressionsClientCapabilities {
+            engine: "ECMAScript".into(),
+            version: Some("ES2020".into()),
+        };
+        assert!(capabilities.is_ecmascript());
+        assert!(capabilities.engine_matches("ecmascript"));
+        assert!(!capabilities.engine_matches("PCRE"));
+
+        let capabilities = RegularExpressionsClientCapabilities {
+            engine: "PCRE".into(),
+            version: None,
+        };
+        assert!(!capabilities.is_ecmascript());
+        assert!(capabilities.engine_matches("PCRE"));
+    }
+
+    #[test]
+    fn workspace_edit_client_capabilities_summary() {
+        assert_eq!(
+            WorkspaceEditClientCapabilities::default().summary(),
+            WorkspaceEditSupport::default()
+        );
+
+        let capabilities: WorkspaceEditClientCapabilities = serde_json::from_str(
+            r#"{"documentChanges":true,"resourceOperations":["create","delete"],"changeAnnotationSupport":{}}"#,
+        )
+        .unwrap();
+        let support = capabilities.summary();
+        assert_eq!(
+            support,
+            WorkspaceEditSupport {
+                document_changes: true,
+                can_create: true,
+                can_rename: false,
+                can_delete: true,
+                annotations: true,
+            }
+        );
+        assert!(support.supports_operation(ResourceOperationKind::Delete));
+        assert!(!support.supports_operation(ResourceOperationKind::Rename));
+    }
+
+    #[test]
+    fn workspace_edit_supported_by() {
+        let rename = WorkspaceEdit {
+            document_changes: Some(DocumentChanges::Operations(vec![
+                DocumentChangeOperation::Op(ResourceOp::Rename(RenameFile {
+                    old_uri: "file:///a.rs".parse().unwrap(),
+                    new_uri: "file:///b.rs".parse().unwrap(),
+                    options: None,
+                    annotation_id: Some("rename".into()),
+                })),
+            ])),
+            ..Default::default()
+        };
+        let mut capabilities = WorkspaceEditClientCapabilities {
+            document_changes: Some(true),
+            resource_operations: Some(vec![ResourceOperationKind::Create]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            rename.is_supported_by(&capabilities),
+            Err(UnsupportedEdit::ResourceOperation(
+                ResourceOperationKind::Rename
+            ))
+        );
+
+        capabilities
+            .resource_operations
+            .as_mut()
+            .unwrap()
+            .push(ResourceOperationKind::Rename);
+        assert_eq!(
+            rename.is_supported_by(&capabilities),
+            Err(UnsupportedEdit::ChangeAnnotations)
+        );
+
+        capabilities.change_annotation_support =
+            Some(ChangeAnnotationWorkspaceEditClientCapabilities::default());
+        assert_eq!(rename.is_supported_by(&capabilities), Ok(()));
+
+        capabilities.document_changes = None;
+        assert_eq!(
+            rename.is_supported_by(&capabilities),
+            Err(UnsupportedEdit::DocumentChanges)
+        );
+
+        let changes = WorkspaceEdit::new(HashMap::new());
+        assert_eq!(
+            changes.is_supported_by(&WorkspaceEditClientCapabilities::default()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn initialize_params_initialization_options_as() {
+        #[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct InitOptions {
+            cargo_features: Vec<String>,
+            check_on_save: bool,
+        }
+
+        let options = InitOptions {
+            cargo_features: vec!["serde".to_string()],
+            check_on_save: true,
+        };
+        let params = InitializeParams {
+            initialization_options: Some(serde_json::to_value(&options).unwrap()),
+            ..Default::default()
+        };
+        let mut params: InitializeParams =
+            serde_json::from_str(&serde_json::to_string(&params).unwrap()).unwrap();
+        assert_eq!(
+            params
+                .initialization_options_as::<InitOptions>()
+                .unwrap()
+                .unwrap(),
+            options
+        );
+        assert!(params.initialization_options_as::<bool>().unwrap().is_err());
+
+        params.initialization_options = None;
+        assert!(params.initialization_options_as::<InitOptions>().is_none());
+    }
+
+    #[test]
+    fn initialize_params_language_tag() {
+        let mut params = InitializeParams {
+            locale: Some("en-US".into()),
+            ..Default::default()
+        };
+        let tag = params.language_tag().unwrap();
+        assert_eq!(tag.language(), "en");
+        assert_eq!(tag.region(), Some("US"));
+
+        let tag = LanguageTag::parse("zh-hant-tw").unwrap();
+        assert_eq!((tag.language(), tag.region()), ("zh", Some("TW")));
+        let tag = LanguageTag::parse("es-419").unwrap();
+        assert_eq!((tag.language(), tag.region()), ("es", Some("419")));
+        let tag = LanguageTag::parse("FR").unwrap();
+        assert_eq!((tag.language(), tag.region()), ("fr", None));
+
+        params.locale = Some("en_US".into());
+        assert_eq!(params.language_tag(), None);
+        assert_eq!(LanguageTag::parse(""), None);
+        assert_eq!(LanguageTag::parse("en--US"), None);
+        assert_eq!(LanguageTag::parse("e1"), None);
+
+        params.locale = None;
+        assert_eq!(params.language_tag(), None);
+    }
+
+    #[test]
+    fn diagnostics_filter_by_severity() {
+        let diag = |severity| Diagnostic {
+            severity,
+            ..Diagnostic::default()
+        };
+        let diags = vec![
+            diag(Some(DiagnosticSeverity::HINT)),
+            diag(Some(DiagnosticSeverity::ERROR)),
+            diag(Some(DiagnosticSeverity::INFORMATION)),
+            diag(Some(DiagnosticSeverity::WARNING)),
+            diag(None),
+        ];
+
+        assert_eq!(
+            filter_by_severity(diags, DiagnosticSeverity::WARNING),
+            vec![
+                diag(Some(DiagnosticSeverity::ERROR)),
+                diag(Some(DiagnosticSeverity::WARNING)),
+                diag(None),
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostic_tags() {
+        let mut diag = Diagnostic::default();
+        assert!(!diag.has_tag(DiagnosticTag::UNNECESSARY));
+
+        assert!(diag.add_tag(DiagnosticTag::UNNECESSARY));
+        assert!(!diag.add_tag(DiagnosticTag::UNNECESSARY));
+        assert!(diag.add_tag(DiagnosticTag::DEPRECATED));
+        assert!(diag.has_tag(DiagnosticTag::UNNECESSARY));
+        assert!(diag.has_tag(DiagnosticTag::DEPRECATED));
+        assert_eq!(
+            diag.tags,
+            Some(vec![DiagnosticTag::UNNECESSARY, DiagnosticTag::DEPRECATED])
+        );
+    }
+
+    #[test]
+    fn diagnostic_message() {
+        let json = r#"{"range":{"start":{"line":0,"character":0},"end":{"line":0,"character":1}},"message":"unused variable"}"#;
+        let diag: Diagnostic = serde_json::from_str(json).unwrap();
+        assert_eq!(diag.message_text(), "unused variable");
+        test_serialization(&diag, json);
+
+        let markup = r#"{"range":{"start":{"line":0,"character":0},"end":{"line":0,"character":1}},"message":{"kind":"markdown","value":"`x` is unused"}}"#;
+        assert!(serde_json::from_str::<Diagnostic>(markup).is_err());
+    }
+
+    #[test]
+    fn diagnostic_display_code() {
+        let diag = |code: Option<NumberOrString>, source: Option<&str>| Diagnostic {
+            code,
+            source: source.map(String::from),
+            ..Diagnostic::default()
+        };
+
+        assert_eq!(
+            diag(Some("E0308".into()), Some("rustc"))
+                .display_code()
+                .as_deref(),
+            Some("rustc(E0308)")
+        );
+        assert_eq!(
+            diag(Some(2322.into()), Some("ts"))
+                .display_code()
+                .as_deref(),
+            Some("ts(2322)")
+        );
+        assert_eq!(
+            diag(Some(2322.into()), None).display_code().as_deref(),
+            Some("2322")
+        );
+        assert_eq!(diag(None, Some("clippy")).display_code(), None);
+    }
+
+    #[test]
+    fn text_document_sync_options() {
+        test_serialization(
+            &TextDocumentSyncOptions::full(),
+            r#"{"openClose":true,"change":1}"#,
+        );
+
+        let capability = TextDocumentSyncCapability::from(TextDocumentSyncOptions {
+            save: Some(true.into()),
+            ..TextDocumentSyncOptions::incremental()
+        });
+        test_serialization(&capability, r#"{"openClose":true,"change":2,"save":true}"#);
+    }
+
+    #[test]
+    fn untagged_disambiguation() {
+        test_serialization(
+            &TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL),
+            r"1",
+        );
+        test_serialization(
+            &TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+                change: Some(TextDocumentSyncKind::FULL),
+                ..Default::default()
+            }),
+            r#"{"change":1}"#,
+        );
+        test_serialization(
+            &TextDocumentSyncCapability::Options(TextDocumentSyncOptions::default()),
+            r"{}",
+        );
+
+        let uri: Uri = "file:///a.rs".parse().unwrap();
+        let range = Range::new(Position::new(0, 0), Position::new(0, 1));
+        test_serialization(
+            &GotoDefinitionResponse::Array(vec![Location::new(uri.clone(), range)]),
+            r#"[{"uri":"file:///a.rs","range":{"start":{"line":0,"character":0},"end":{"line":0,"character":1}}}]"#,
+        );
+        test_serialization(
+            &GotoDefinitionResponse::Link(vec![LocationLink {
+                origin_selection_range: None,
+                target_uri: uri.clone(),
+                target_range: range,
+                target_selection_range: range,
+            }]),
+            r#"[{"targetUri":"file:///a.rs","targetRange":{"start":{"line":0,"character":0},"end":{"line":0,"character":1}},"targetSelectionRange":{"start":{"line":0,"character":0},"end":{"line":0,"character":1}}}]"#,
+        );
+
+        test_serialization(
+            &TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+                edits: vec![
+                    OneOf::Left(TextEdit::new(range, "a".into())),
+                    OneOf::Right(AnnotatedTextEdit {
+                        text_edit: TextEdit::new(range, "b".into()),
+                        annotation_id: "id".into(),
+                    }),
+                ],
+            },
+            r#"{"textDocument":{"uri":"file:///a.rs","version":null},"edits":[{"range":{"start":{"line":0,"character":0},"end":{"line":0,"character":1}},"newText":"a"},{"range":{"start":{"line":0,"character":0},"end":{"line":0,"character":1}},"newText":"b","annotationId":"id"}]}"#,
+        );
+    }
+
+    #[test]
+    fn code_description_from_url() {
+        let description = CodeDescription::from_url("https://example.com/lints#E001").unwrap();
+        assert_eq!(description.href.as_str(), "https://example.com/lints#E001");
+        assert!(CodeDescription::from_url("HTTP://example.com").is_ok());
+
+        assert!(matches!(
+            CodeDescription::from_url("file:///etc/passwd"),
+            Err(CodeDescriptionError::UnsupportedScheme(scheme)) if scheme == "file"
+        ));
+        assert!(matches!(
+            CodeDescription::from_url("javascript:alert(1)"),
+            Err(CodeDescriptionError::UnsupportedScheme(_))
+        ));
+        assert!(matches!(
+            CodeDescription::from_url("not a url"),
+            Err(CodeDescriptionError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn watched_files_registration_from_globs() {
+        test_serialization(
+            &DidChangeWatchedFilesRegistrationOptions::from_globs(&["**/*.rs", "**/Cargo.toml"]),
+            r#"{"watchers":[{"globPattern":"**/*.rs"},{"globPattern":"**/Cargo.toml"}]}"#,
+        );
+    }
+
+    #[test]
+    fn server_capabilities_effective_sync_kind() {
+        let mut capabilities = ServerCapabilities::default();
+        assert_eq!(
+            capabilities.effective_sync_kind(),
+            TextDocumentSyncKind::NONE
+        );
+
+        capabilities.text_document_sync = Some(TextDocumentSyncKind::FULL.into());
+        assert_eq!(
+            capabilities.effective_sync_kind(),
+            TextDocumentSyncKind::FULL
+        );
+
+        capabilities.text_document_sync = Some(TextDocumentSyncOptions::incremental().into());
+        assert_eq!(
+            capabilities.effective_sync_kind(),
+            TextDocumentSyncKind::INCREMENTAL
+        );
+
+        capabilities.text_document_sync = Some(TextDocumentSyncOptions::default().into());
+        assert_eq!(
+            capabilities.effective_sync_kind(),
+            TextDocumentSyncKind::NONE
+        );
+    }
+
     #[test]
     fn root_uri_can_be_missing() {
         serde_json::from_str::<InitializeParams>(r#"{ "capabilities": {} }"#).unwrap();
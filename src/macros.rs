@@ -77,6 +77,19 @@ macro_rules! lsp_enum {
             }
         }
 
+        impl std::fmt::Display for $typ {
+            /// Writes the constant name as declared, e.g. `TYPE_PARAMETER`, or
+            /// `Unknown(99)` for a value without one.
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match *self {
+                    $(
+                        Self::$name => f.write_str(stringify!($name)),
+                    )*
+                    _ => write!(f, "Unknown({})", self.0),
+                }
+            }
+        }
+
         impl std::convert::TryFrom<&str> for $typ {
             type Error = &'static str;
             fn try_from(value: &str) -> Result<Self, Self::Error> {
@@ -90,3 +90,58 @@ pub struct Moniker {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kind: Option<MonikerKind>,
 }
+
+impl Moniker {
+    /// The scheme of monikers emitted by the TypeScript compiler.
+    pub const SCHEME_TSC: &'static str = "tsc";
+
+    /// The scheme of monikers of npm packages.
+    pub const SCHEME_NPM: &'static str = "npm";
+
+    /// The scheme of monikers of Python packages published on the Python Package Index.
+    pub const SCHEME_PYPI: &'static str = "pypi";
+
+    /// The scheme of monikers of Maven artifacts.
+    pub const SCHEME_MAVEN: &'static str = "maven";
+
+    /// The scheme of monikers of .NET packages published on nuget.org.
+    pub const SCHEME_NUGET: &'static str = "nuget";
+
+    /// The scheme of monikers of Cargo crates.
+    pub const SCHEME_CARGO: &'static str = "cargo";
+
+    /// The scheme of monikers of Go modules.
+    pub const SCHEME_GOMOD: &'static str = "gomod";
+
+    /// Whether the moniker is globally unique, and can thus be matched
+    /// against monikers from other repositories.
+    #[must_use]
+    pub fn is_global(&self) -> bool {
+        self.unique == UniquenessLevel::Global
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_deserialization;
+
+    #[test]
+    fn moniker_is_global() {
+        let mut moniker = Moniker {
+            scheme: Moniker::SCHEME_NPM.to_string(),
+            identifier: "lodash::debounce".to_string(),
+            unique: UniquenessLevel::Global,
+            kind: Some(MonikerKind::Import),
+        };
+        test_deserialization(
+            r#"{"scheme":"npm","identifier":"lodash::debounce","unique":"global","kind":"import"}"#,
+            &moniker,
+        );
+        assert!(moniker.is_global());
+
+        moniker.scheme = Moniker::SCHEME_TSC.to_string();
+        moniker.unique = UniquenessLevel::Scheme;
+        assert!(!moniker.is_global());
+    }
+}
@@ -127,6 +127,60 @@ pub struct NotebookDocumentSyncOptions {
     pub save: Option<bool>,
 }
 
+impl NotebookDocumentSyncOptions {
+    /// Creates options without any notebook selector.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            notebook_selector: Vec::new(),
+            save: None,
+        }
+    }
+
+    /// Syncs notebooks of type `notebook_type` (`*` matches every notebook),
+    /// limited to cells in one of `languages` when it is non-empty.
+    #[must_use]
+    pub fn notebook<I>(mut self, notebook_type: impl Into<String>, languages: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let cells = NotebookCellSelector::languages(languages);
+        self.notebook_selector.push(NotebookSelector::ByNotebook {
+            notebook: Notebook::String(notebook_type.into()),
+            cells: (!cells.is_empty()).then_some(cells),
+        });
+        self
+    }
+
+    /// Syncs the cells in one of `languages`, in any notebook.
+    #[must_use]
+    pub fn cells<I>(mut self, languages: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.notebook_selector.push(NotebookSelector::ByCells {
+            notebook: None,
+            cells: NotebookCellSelector::languages(languages),
+        });
+        self
+    }
+
+    /// Sets whether save notifications are forwarded to the server.
+    #[must_use]
+    pub const fn save(mut self, save: bool) -> Self {
+        self.save = Some(save);
+        self
+    }
+}
+
+impl Default for NotebookDocumentSyncOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Registration options specific to a notebook.
 ///
 /// @since 3.17.0
@@ -194,6 +248,27 @@ pub struct NotebookCellSelector {
     pub language: String,
 }
 
+impl NotebookCellSelector {
+    /// Creates a selector matching cells of `language`.
+    #[must_use]
+    pub fn new(language: impl Into<String>) -> Self {
+        Self {
+            language: language.into(),
+        }
+    }
+
+    fn languages<I>(languages: I) -> Vec<Self>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        languages
+            .into_iter()
+            .map(|language| Self::new(language.as_ref()))
+            .collect()
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum Notebook {
@@ -404,3 +479,25 @@ mod notification_params {
         pub cell_text_documents: Vec<TextDocumentIdentifier>,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_serialization;
+
+    #[test]
+    fn notebook_document_sync_options_builder() {
+        test_serialization(
+            &NotebookDocumentSyncOptions::new()
+                .cells(&["python"])
+                .save(true),
+            r#"{"notebookSelector":[{"cells":[{"language":"python"}]}],"save":true}"#,
+        );
+        test_serialization(
+            &NotebookDocumentSyncOptions::new()
+                .notebook("jupyter-notebook", ["python", "r"])
+                .notebook("*", [] as [&str; 0]),
+            r#"{"notebookSelector":[{"notebook":"jupyter-notebook","cells":[{"language":"python"},{"language":"r"}]},{"notebook":"*"}]}"#,
+        );
+    }
+}
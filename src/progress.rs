@@ -16,6 +16,51 @@ pub struct ProgressParams {
     pub value: ProgressParamsValue,
 }
 
+/// A `$/progress` notification reporting a partial result.
+///
+/// Requests that accept a `partialResultToken` (see `PartialResultParams`) may
+/// stream their result to the client. The `value` of each notification has the
+/// same shape as the request's final result, and the client appends the values
+/// in the order they are received. Once partial results have been reported, the
+/// final response must be empty (e.g. an empty array).
+///
+/// For example, a server answering `textDocument/references` in batches:
+///
+/// ```
+/// # use ls_types::{Location, PartialResultProgressParams, Position, Range, ReferenceParams};
+/// # fn batch() -> Vec<Location> {
+/// #     let uri = "file:///a.rs".parse().unwrap();
+/// #     vec![Location::new(uri, Range::new(Position::new(0, 0), Position::new(0, 3)))]
+/// # }
+/// # fn send(_: &str, _: serde_json::Value) {}
+/// fn references(params: &ReferenceParams) -> Vec<Location> {
+///     let Some(token) = &params.partial_result_params.partial_result_token else {
+///         return batch();
+///     };
+///     for _ in 0..2 {
+///         let progress = PartialResultProgressParams::new(token.clone(), batch());
+///         send("$/progress", serde_json::to_value(progress).unwrap());
+///     }
+///     Vec::new()
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialResultProgressParams<T> {
+    /// The partial result token provided by the client.
+    pub token: ProgressToken,
+
+    /// The partial result, shaped like the request's final result.
+    pub value: T,
+}
+
+impl<T> PartialResultProgressParams<T> {
+    #[must_use]
+    pub const fn new(token: ProgressToken, value: T) -> Self {
+        Self { token, value }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone)]
 #[serde(untagged)]
 pub enum ProgressParamsValue {
@@ -56,6 +101,22 @@ pub struct WorkDoneProgressParams {
     pub work_done_token: Option<ProgressToken>,
 }
 
+impl WorkDoneProgressParams {
+    /// Creates params allowing work done progress to be reported with `token`.
+    #[must_use]
+    pub const fn with_token(token: ProgressToken) -> Self {
+        Self {
+            work_done_token: Some(token),
+        }
+    }
+
+    /// The token to report work done progress with, if the client provided one.
+    #[must_use]
+    pub const fn token(&self) -> Option<&ProgressToken> {
+        self.work_done_token.as_ref()
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Default, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkDoneProgressBegin {
@@ -125,6 +186,11 @@ pub struct WorkDoneProgressEnd {
     pub message: Option<String>,
 }
 
+/// The value of a `$/progress` notification reporting work done progress,
+/// tagged by its `kind`.
+///
+/// Like the crate's other `kind`-tagged unions, this is closed: the protocol
+/// defines exactly these three kinds, so any other `kind` fails to deserialize.
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone)]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum WorkDoneProgress {
@@ -132,3 +198,63 @@ pub enum WorkDoneProgress {
     Report(WorkDoneProgressReport),
     End(WorkDoneProgressEnd),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Location, PartialResultParams, Position, Range, tests::test_serialization};
+
+    #[test]
+    fn work_done_progress_params_token() {
+        let params = WorkDoneProgressParams::with_token(ProgressToken::Number(1));
+        assert_eq!(params.token(), Some(&ProgressToken::Number(1)));
+        test_serialization(&params, r#"{"workDoneToken":1}"#);
+
+        let params = WorkDoneProgressParams::default();
+        assert_eq!(params.token(), None);
+        test_serialization(&params, r"{}");
+    }
+
+    #[test]
+    fn work_done_progress_kind() {
+        test_serialization(
+            &WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                title: "Indexing".to_string(),
+                cancellable: Some(false),
+                message: None,
+                percentage: Some(0),
+            }),
+            r#"{"kind":"begin","title":"Indexing","cancellable":false,"percentage":0}"#,
+        );
+        test_serialization(
+            &WorkDoneProgress::Report(WorkDoneProgressReport {
+                message: Some("3/25 files".to_string()),
+                percentage: Some(12),
+                ..Default::default()
+            }),
+            r#"{"kind":"report","message":"3/25 files","percentage":12}"#,
+        );
+        test_serialization(
+            &WorkDoneProgress::End(WorkDoneProgressEnd::default()),
+            r#"{"kind":"end"}"#,
+        );
+
+        let err = serde_json::from_str::<WorkDoneProgress>(r#"{"kind":"pause"}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown variant `pause`"), "{err}");
+        let err = serde_json::from_str::<WorkDoneProgress>(r#"{"title":"x"}"#).unwrap_err();
+        assert!(err.to_string().contains("missing field `kind`"), "{err}");
+    }
+
+    #[test]
+    fn partial_result_progress() {
+        let params = PartialResultParams::with_token("partial".into());
+        let token = params.partial_result_token.unwrap();
+        let uri = "file:///a.rs".parse().unwrap();
+        let range = Range::new(Position::new(1, 2), Position::new(1, 5));
+
+        test_serialization(
+            &PartialResultProgressParams::new(token, vec![Location::new(uri, range)]),
+            r#"{"token":"partial","value":[{"uri":"file:///a.rs","range":{"start":{"line":1,"character":2},"end":{"line":1,"character":5}}}]}"#,
+        );
+    }
+}
@@ -1067,4 +1067,21 @@ mod test {
         check_macro!("typeHierarchy/supertypes");
         check_macro!("workspaceSymbol/resolve");
     }
+
+    fn check_refresh<R>(method: &str)
+    where
+        R: Request<Params = (), Result = ()>,
+    {
+        assert_eq!(R::METHOD, method);
+        serde_json::from_str::<R::Result>("null").unwrap();
+    }
+
+    #[test]
+    fn check_refresh_requests() {
+        check_refresh::<InlayHintRefreshRequest>("workspace/inlayHint/refresh");
+        check_refresh::<InlineValueRefreshRequest>("workspace/inlineValue/refresh");
+        check_refresh::<SemanticTokensRefresh>("workspace/semanticTokens/refresh");
+        check_refresh::<CodeLensRefresh>("workspace/codeLens/refresh");
+        check_refresh::<WorkspaceDiagnosticRefresh>("workspace/diagnostic/refresh");
+    }
 }
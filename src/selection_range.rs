@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     PartialResultParams, Position, Range, StaticTextDocumentRegistrationOptions,
-    TextDocumentIdentifier, WorkDoneProgressOptions, WorkDoneProgressParams,
+    TextDocumentIdentifier, Uri, WorkDoneProgressOptions, WorkDoneProgressParams,
 };
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -64,6 +64,9 @@ pub struct SelectionRangeParams {
     pub text_document: TextDocumentIdentifier,
 
     /// The positions inside the text document.
+    ///
+    /// The response holds one [`SelectionRange`] per position, at the same
+    /// index, whose range contains that position.
     pub positions: Vec<Position>,
 
     #[serde(flatten)]
@@ -73,6 +76,20 @@ pub struct SelectionRangeParams {
     pub partial_result_params: PartialResultParams,
 }
 
+impl SelectionRangeParams {
+    /// Creates params asking for the selection ranges at `positions` in the
+    /// document at `uri`.
+    #[must_use]
+    pub fn new(uri: Uri, positions: Vec<Position>) -> Self {
+        Self {
+            text_document: TextDocumentIdentifier::new(uri),
+            positions,
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        }
+    }
+}
+
 /// Represents a selection range.
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -84,3 +101,20 @@ pub struct SelectionRange {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<Box<SelectionRange>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_serialization;
+
+    #[test]
+    fn selection_range_params() {
+        test_serialization(
+            &SelectionRangeParams::new(
+                "file:///a.rs".parse().unwrap(),
+                vec![Position::new(1, 2), Position::new(3, 4)],
+            ),
+            r#"{"textDocument":{"uri":"file:///a.rs"},"positions":[{"line":1,"character":2},{"line":3,"character":4}]}"#,
+        );
+    }
+}
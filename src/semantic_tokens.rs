@@ -148,6 +148,77 @@ pub struct SemanticTokensLegend {
     pub token_modifiers: Vec<SemanticTokenModifier>,
 }
 
+impl SemanticTokensLegend {
+    /// Starts building a legend, handing out the index of each registered entry.
+    #[must_use]
+    pub fn builder() -> SemanticTokensLegendBuilder {
+        SemanticTokensLegendBuilder::default()
+    }
+
+    /// Returns the index of the token type named `name`, to be used as
+    /// `SemanticToken::token_type`.
+    #[must_use]
+    pub fn type_index(&self, name: &str) -> Option<u32> {
+        position(&self.token_types, |ty| ty.as_str() == name)
+    }
+
+    /// Returns the index of the token modifier named `name`. The modifier is
+    /// set in `SemanticToken::token_modifiers_bitset` as `1 << index`.
+    #[must_use]
+    pub fn modifier_index(&self, name: &str) -> Option<u32> {
+        position(&self.token_modifiers, |modifier| modifier.as_str() == name)
+    }
+}
+
+fn position<T>(items: &[T], predicate: impl FnMut(&T) -> bool) -> Option<u32> {
+    items
+        .iter()
+        .position(predicate)
+        .and_then(|index| u32::try_from(index).ok())
+}
+
+/// Builds a [`SemanticTokensLegend`], keeping token indices in sync with the
+/// legend arrays.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct SemanticTokensLegendBuilder {
+    legend: SemanticTokensLegend,
+}
+
+impl SemanticTokensLegendBuilder {
+    /// Registers a token type and returns its index. Registering the same
+    /// type twice returns the index of the first registration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the legend holds more than `u32::MAX` token types.
+    pub fn token_type(&mut self, token_type: SemanticTokenType) -> u32 {
+        register(&mut self.legend.token_types, token_type)
+    }
+
+    /// Registers a token modifier and returns its index. Registering the same
+    /// modifier twice returns the index of the first registration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the legend holds more than `u32::MAX` token modifiers.
+    pub fn token_modifier(&mut self, token_modifier: SemanticTokenModifier) -> u32 {
+        register(&mut self.legend.token_modifiers, token_modifier)
+    }
+
+    #[must_use]
+    pub fn build(self) -> SemanticTokensLegend {
+        self.legend
+    }
+}
+
+fn register<T: PartialEq>(items: &mut Vec<T>, item: T) -> u32 {
+    let index = items.iter().position(|i| *i == item).unwrap_or_else(|| {
+        items.push(item);
+        items.len() - 1
+    });
+    u32::try_from(index).expect("semantic tokens legend is too large")
+}
+
 /// The actual tokens.
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
 pub struct SemanticToken {
@@ -230,6 +301,58 @@ impl SemanticToken {
     }
 }
 
+/// A semantic token at an absolute document position, as opposed to the
+/// relative [`SemanticToken`] sent over the wire.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct AbsoluteSemanticToken {
+    pub line: u32,
+    pub start: u32,
+    pub length: u32,
+    pub token_type: u32,
+    pub token_modifiers: u32,
+}
+
+/// Delta-encodes absolute tokens into the `data` of a [`SemanticTokens`]
+/// result, leaving `result_id` unset.
+///
+/// Tokens are sorted by position before encoding, so they may be given in any
+/// order.
+pub fn encode_semantic_tokens<I>(tokens: I) -> SemanticTokens
+where
+    I: IntoIterator<Item = AbsoluteSemanticToken>,
+{
+    let mut tokens: Vec<_> = tokens.into_iter().collect();
+    tokens.sort_by_key(|token| (token.line, token.start));
+
+    let mut previous_line = 0;
+    let mut previous_start = 0;
+    let data = tokens
+        .into_iter()
+        .map(|token| {
+            let delta_line = token.line - previous_line;
+            let delta_start = if delta_line == 0 {
+                token.start - previous_start
+            } else {
+                token.start
+            };
+            previous_line = token.line;
+            previous_start = token.start;
+            SemanticToken {
+                delta_line,
+                delta_start,
+                length: token.length,
+                token_type: token.token_type,
+                token_modifiers_bitset: token.token_modifiers,
+            }
+        })
+        .collect();
+
+    SemanticTokens {
+        result_id: None,
+        data,
+    }
+}
+
 /// @since 3.16.0
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -330,6 +453,87 @@ pub struct SemanticTokensDelta {
     pub edits: Vec<SemanticTokensEdit>,
 }
 
+impl SemanticTokensDelta {
+    /// Applies the edits of this delta to the raw `data` of the previous
+    /// [`SemanticTokens`] result, as a client would.
+    ///
+    /// Edits refer to positions in the previous data, so they are applied from
+    /// the last to the first. Out-of-range positions are clamped.
+    pub fn apply(&self, data: &mut Vec<u32>) {
+        let mut edits: Vec<_> = self.edits.iter().collect();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+        for edit in edits {
+            let start = (edit.start as usize).min(data.len());
+            let end = start
+                .saturating_add(edit.delete_count as usize)
+                .min(data.len());
+            let tokens = edit.data.iter().flatten().flat_map(|token| {
+                [
+                    token.delta_line,
+                    token.delta_start,
+                    token.length,
+                    token.token_type,
+                    token.token_modifiers_bitset,
+                ]
+            });
+            data.splice(start..end, tokens);
+        }
+    }
+}
+
+/// Computes a delta turning the raw `previous` token data into `next`, to
+/// answer a `textDocument/semanticTokens/full/delta` request.
+///
+/// The delta is a single edit replacing the tokens between the common prefix
+/// and suffix of both arrays, or no edit if they are equal. Both arrays must
+/// hold whole tokens of 5 integers each. `result_id` is left unset.
+#[must_use]
+pub fn diff_semantic_tokens(previous: &[u32], next: &[u32]) -> SemanticTokensDelta {
+    let previous: Vec<_> = previous.chunks_exact(5).collect();
+    let next: Vec<_> = next.chunks_exact(5).collect();
+
+    let prefix = previous
+        .iter()
+        .zip(&next)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = previous[prefix..]
+        .iter()
+        .rev()
+        .zip(next[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let deleted = previous.len() - prefix - suffix;
+    let inserted = &next[prefix..next.len() - suffix];
+    let edits = if deleted == 0 && inserted.is_empty() {
+        Vec::new()
+    } else {
+        let to_u32 = |tokens: usize| u32::try_from(tokens * 5).unwrap_or(u32::MAX);
+        vec![SemanticTokensEdit {
+            start: to_u32(prefix),
+            delete_count: to_u32(deleted),
+            data: (!inserted.is_empty()).then(|| {
+                inserted
+                    .iter()
+                    .map(|chunk| SemanticToken {
+                        delta_line: chunk[0],
+                        delta_start: chunk[1],
+                        length: chunk[2],
+                        token_type: chunk[3],
+                        token_modifiers_bitset: chunk[4],
+                    })
+                    .collect()
+            }),
+        }]
+    };
+
+    SemanticTokensDelta {
+        result_id: None,
+        edits,
+    }
+}
+
 /// Capabilities specific to the `textDocument/semanticTokens/*` requests.
 ///
 /// @since 3.16.0
@@ -392,6 +596,33 @@ pub struct SemanticTokensClientCapabilities {
     pub augments_syntax_tokens: Option<bool>,
 }
 
+impl SemanticTokensClientCapabilities {
+    /// Whether the client sends `textDocument/semanticTokens/range` requests.
+    #[must_use]
+    pub const fn supports_range(&self) -> bool {
+        matches!(self.requests.range, Some(true))
+    }
+
+    /// Whether the client sends `textDocument/semanticTokens/full` requests.
+    #[must_use]
+    pub const fn supports_full(&self) -> bool {
+        !matches!(
+            self.requests.full,
+            None | Some(SemanticTokensFullOptions::Bool(false))
+        )
+    }
+
+    /// Whether the client sends `textDocument/semanticTokens/full/delta`
+    /// requests.
+    #[must_use]
+    pub const fn supports_delta(&self) -> bool {
+        matches!(
+            self.requests.full,
+            Some(SemanticTokensFullOptions::Delta { delta: Some(true) })
+        )
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTokensClientCapabilitiesRequests {
@@ -650,6 +881,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_semantic_tokens_legend_builder() {
+        let mut builder = SemanticTokensLegend::builder();
+        let function = builder.token_type(SemanticTokenType::FUNCTION);
+        let variable = builder.token_type(SemanticTokenType::VARIABLE);
+        let readonly = builder.token_modifier(SemanticTokenModifier::READONLY);
+        assert_eq!((function, variable, readonly), (0, 1, 0));
+        assert_eq!(builder.token_type(SemanticTokenType::FUNCTION), function);
+
+        let legend = builder.build();
+        assert_eq!(
+            legend.token_types,
+            vec![SemanticTokenType::FUNCTION, SemanticTokenType::VARIABLE]
+        );
+        assert_eq!(legend.type_index("variable"), Some(variable));
+        assert_eq!(legend.type_index("macro"), None);
+        assert_eq!(legend.modifier_index("readonly"), Some(readonly));
+        assert_eq!(legend.modifier_index("static"), None);
+    }
+
     #[test]
     #[should_panic = "Length is not divisible by 5"]
     fn test_semantic_tokens_support_deserialization_err() {
@@ -727,4 +978,93 @@ mod tests {
             r#"{"start":0,"deleteCount":1}"#,
         );
     }
+
+    #[test]
+    fn test_semantic_tokens_client_requests() {
+        let mut capabilities: SemanticTokensClientCapabilities = serde_json::from_str(
+            r#"{"requests":{"range":true,"full":{"delta":true}},"tokenTypes":[],"tokenModifiers":[],"formats":["relative"]}"#,
+        )
+        .unwrap();
+        assert!(capabilities.supports_range());
+        assert!(capabilities.supports_full());
+        assert!(capabilities.supports_delta());
+
+        capabilities.requests.full = Some(SemanticTokensFullOptions::Delta { delta: None });
+        assert!(capabilities.supports_full());
+        assert!(!capabilities.supports_delta());
+
+        capabilities.requests = SemanticTokensClientCapabilitiesRequests {
+            range: Some(false),
+            full: Some(SemanticTokensFullOptions::Bool(true)),
+        };
+        assert!(!capabilities.supports_range());
+        assert!(capabilities.supports_full());
+        assert!(!capabilities.supports_delta());
+
+        capabilities.requests = SemanticTokensClientCapabilitiesRequests::default();
+        assert!(!capabilities.supports_range());
+        assert!(!capabilities.supports_full());
+        assert!(!capabilities.supports_delta());
+    }
+
+    #[test]
+    fn test_encode_semantic_tokens() {
+        let token = |line, start, length, token_type, token_modifiers| AbsoluteSemanticToken {
+            line,
+            start,
+            length,
+            token_type,
+            token_modifiers,
+        };
+        let tokens = encode_semantic_tokens([
+            token(3, 2, 7, 2, 0),
+            token(2, 5, 3, 0, 3),
+            token(2, 10, 4, 1, 0),
+            token(5, 0, 1, 0, 1),
+        ]);
+        test_serialization(
+            &tokens,
+            r#"{"data":[2,5,3,0,3,0,5,4,1,0,1,2,7,2,0,2,0,1,0,1]}"#,
+        );
+
+        assert_eq!(encode_semantic_tokens([]), SemanticTokens::default());
+    }
+
+    #[test]
+    fn test_diff_semantic_tokens() {
+        let previous = [
+            0, 0, 3, 1, 0, //
+            1, 4, 5, 2, 0, //
+            0, 6, 2, 0, 0, //
+            2, 0, 4, 1, 1,
+        ];
+        let next = [
+            0, 0, 3, 1, 0, //
+            1, 4, 7, 2, 0, //
+            0, 8, 2, 0, 0, //
+            0, 3, 1, 3, 0, //
+            2, 0, 4, 1, 1,
+        ];
+
+        let delta = diff_semantic_tokens(&previous, &next);
+        test_serialization(
+            &delta,
+            r#"{"edits":[{"start":5,"deleteCount":10,"data":[1,4,7,2,0,0,8,2,0,0,0,3,1,3,0]}]}"#,
+        );
+
+        let mut data = previous.to_vec();
+        delta.apply(&mut data);
+        assert_eq!(data, next);
+
+        let delta = diff_semantic_tokens(&next, &previous);
+        let mut data = next.to_vec();
+        delta.apply(&mut data);
+        assert_eq!(data, previous);
+
+        assert!(diff_semantic_tokens(&previous, &previous).edits.is_empty());
+        test_serialization(
+            &diff_semantic_tokens(&previous, &previous[..10]),
+            r#"{"edits":[{"start":10,"deleteCount":10}]}"#,
+        );
+    }
 }
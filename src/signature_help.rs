@@ -154,6 +154,35 @@ pub struct SignatureHelp {
     pub active_parameter: Option<u32>,
 }
 
+impl SignatureHelp {
+    /// The response of a `textDocument/signatureHelp` request telling the
+    /// client there is no signature help at the position, i.e. `null`.
+    ///
+    /// This is not the same as an empty `signatures` list: clients treat
+    /// `null` as "nothing to show" and close any open signature help, while an
+    /// empty list is a (useless) signature help some clients still display.
+    #[must_use]
+    pub const fn none() -> Option<Self> {
+        None
+    }
+
+    /// Resolves the active parameter of the active signature.
+    ///
+    /// The active signature defaults to the first one when `active_signature`
+    /// is omitted or out of range, and its own `active_parameter` takes
+    /// precedence over the top-level one.
+    #[must_use]
+    pub fn effective_active_parameter(&self) -> Option<u32> {
+        let active_signature = self
+            .active_signature
+            .and_then(|index| self.signatures.get(usize::try_from(index).ok()?))
+            .or_else(|| self.signatures.first());
+        active_signature
+            .and_then(|signature| signature.active_parameter)
+            .or(self.active_parameter)
+    }
+}
+
 /// Represents the signature of something callable. A signature
 /// can have a label, like a function-name, a doc-comment, and
 /// a set of parameters.
@@ -206,3 +235,55 @@ pub enum ParameterLabel {
     Simple(String),
     LabelOffsets([u32; 2]),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{test_deserialization, test_serialization};
+
+    #[test]
+    fn test_effective_active_parameter() {
+        let signature = |label: &str, active_parameter| SignatureInformation {
+            label: label.into(),
+            documentation: None,
+            parameters: None,
+            active_parameter,
+        };
+        let mut help = SignatureHelp {
+            signatures: vec![signature("f(a, b)", None), signature("f(a, b, c)", Some(2))],
+            active_signature: Some(1),
+            active_parameter: Some(1),
+        };
+        test_deserialization(
+            r#"{"signatures":[{"label":"f(a, b)"},{"label":"f(a, b, c)","activeParameter":2}],"activeSignature":1,"activeParameter":1}"#,
+            &help,
+        );
+        assert_eq!(help.effective_active_parameter(), Some(2));
+
+        help.active_signature = Some(0);
+        assert_eq!(help.effective_active_parameter(), Some(1));
+
+        help.active_signature = Some(5);
+        assert_eq!(help.effective_active_parameter(), Some(1));
+
+        help.active_parameter = None;
+        assert_eq!(help.effective_active_parameter(), None);
+    }
+
+    #[test]
+    fn test_none_vs_empty() {
+        test_serialization(&SignatureHelp::none(), "null");
+        test_serialization(
+            &Some(SignatureHelp {
+                signatures: vec![],
+                active_signature: None,
+                active_parameter: None,
+            }),
+            r#"{"signatures":[]}"#,
+        );
+        assert_eq!(
+            serde_json::from_str::<Option<SignatureHelp>>("null").unwrap(),
+            None
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     borrow::Cow,
-    hash::Hash,
+    hash::{BuildHasherDefault, DefaultHasher, Hash, Hasher},
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
     str::FromStr,
@@ -149,6 +149,18 @@ fn capitalize_drive_letter(path: &str) -> String {
     }
 }
 
+/// Whether `path` starts with a Windows drive letter, e.g. `C:\` or `C:/`,
+/// or a UNC prefix `\\`.
+#[cfg(not(windows))]
+fn is_windows_style_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let drive = bytes.len() >= 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes.get(2), None | Some(b'\\' | b'/'));
+    drive || path.starts_with("\\\\")
+}
+
 const ASCII_SET: AsciiSet =
     // RFC3986 allows only alphanumeric characters, `-`, `.`, `_`, and `~` in the path.
     percent_encoding::NON_ALPHANUMERIC
@@ -159,6 +171,28 @@ const ASCII_SET: AsciiSet =
         // we do not want path separators to be percent-encoded
         .remove(b'/');
 
+const FRAGMENT_SET: AsciiSet = percent_encoding::NON_ALPHANUMERIC
+    // RFC3986 fragments allow unreserved characters, sub-delimiters, `:`, `@`, `/` and `?`.
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b'!')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b'*')
+    .remove(b'+')
+    .remove(b',')
+    .remove(b';')
+    .remove(b'=')
+    .remove(b':')
+    .remove(b'@')
+    .remove(b'/')
+    .remove(b'?');
+
 /// Provide methods to [`Uri`] to fill blanks left by
 /// `fluent_uri` (the underlying type) especially when converting to and from file paths.
 impl Uri {
@@ -170,8 +204,14 @@ impl Uri {
     /// responsibility to check the URL’s scheme before calling this.
     ///
     /// e.g. `Uri("file:///etc/passwd")` becomes `PathBuf("/etc/passwd")`
+    ///
+    /// Returns `None` for an entry of an archive, see [`Uri::inner_jar_target`].
     #[must_use]
     pub fn to_file_path(&self) -> Option<Cow<'_, Path>> {
+        if self.inner_jar_target().is_some() {
+            return None;
+        }
+
         let path_str = self.path().decode().to_string_lossy();
         if path_str.is_empty() {
             return None;
@@ -206,12 +246,311 @@ impl Uri {
         }
     }
 
+    /// Whether this is a `file` URI that [`Uri::to_file_path`] converts to an
+    /// absolute path, so that servers can reject unusable URIs up front.
+    ///
+    /// e.g. `file:///C:/x` is valid, but `file://` has no path and parses
+    /// fine nonetheless. `file:///` is the root directory on Unix, but has no
+    /// usable path on Windows.
+    #[must_use]
+    pub fn is_valid_file_uri(&self) -> bool {
+        self.scheme().as_str().eq_ignore_ascii_case("file")
+            && self.to_file_path().is_some_and(|path| path.is_absolute())
+    }
+
+    /// Whether this URI refers to the file at `path`, e.g. to find the open
+    /// document of a path the server knows about.
+    ///
+    /// The path is converted with [`Uri::from_file_path`], so a relative path
+    /// must exist to be resolved. Both URIs are then compared by their
+    /// [`Uri::comparison_key`], ignoring ASCII case on Windows where file
+    /// names are case-insensitive.
+    #[must_use]
+    pub fn refers_to_path(&self, path: &Path) -> bool {
+        let Some(uri) = Self::from_file_path(path) else {
+            return false;
+        };
+        let (key, other) = (self.comparison_key(), uri.comparison_key());
+        if cfg!(windows) {
+            key.eq_ignore_ascii_case(&other)
+        } else {
+            key == other
+        }
+    }
+
+    /// Like [`Uri::to_file_path`], but decodes the path into raw bytes so
+    /// that non-UTF-8 file names are preserved instead of being replaced lossily.
+    ///
+    /// e.g. `Uri("file:///tmp/%FF.txt")` becomes `PathBuf(b"/tmp/\xFF.txt")`
+    #[cfg(unix)]
+    #[must_use]
+    pub fn to_file_path_os(&self) -> Option<PathBuf> {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        if self.inner_jar_target().is_some() {
+            return None;
+        }
+
+        let bytes = self.path().decode().to_bytes();
+        if bytes.is_empty() {
+            return None;
+        }
+
+        Some(PathBuf::from(OsStr::from_bytes(&bytes)))
+    }
+
+    /// Returns the percent-decoded path of this URI relative to `base`, e.g.
+    /// `file:///proj/src/a.rs` relative to `file:///proj` is `src/a.rs`.
+    ///
+    /// Returns `None` if the URIs differ in scheme or authority, or if this
+    /// URI is not strictly below `base`.
+    #[must_use]
+    pub fn relative_to(&self, base: &Self) -> Option<String> {
+        if !self
+            .scheme()
+            .as_str()
+            .eq_ignore_ascii_case(base.scheme().as_str())
+            || self.authority().map(|a| a.as_str()) != base.authority().map(|a| a.as_str())
+        {
+            return None;
+        }
+
+        let base_path = base.path().as_str().trim_end_matches('/');
+        let relative = self
+            .path()
+            .as_str()
+            .strip_prefix(base_path)?
+            .strip_prefix('/')?;
+        if relative.is_empty() {
+            return None;
+        }
+
+        Some(
+            percent_encoding::percent_decode_str(relative)
+                .decode_utf8_lossy()
+                .into_owned(),
+        )
+    }
+
+    /// Returns a normalized string suitable as a stable key when comparing or
+    /// hashing URIs that may be spelled differently by different clients.
+    ///
+    /// The scheme and host are lowercased, percent-encodings are normalized
+    /// (unreserved characters decoded, hex digits uppercased), dot segments are
+    /// removed and, for `file` URIs, a Windows drive letter (`C:` or `c%3A`) is
+    /// folded to lowercase `c:` whatever the platform, since such URIs may be
+    /// produced by a Windows client and handled elsewhere.
+    ///
+    /// **Note:** the key is only meant for comparisons, it is not a valid URI.
+    #[must_use]
+    pub fn comparison_key(&self) -> String {
+        let normalized = self.0.normalize();
+        let mut key = format!("{}:", normalized.scheme().as_str());
+        if let Some(authority) = normalized.authority() {
+            key.push_str("//");
+            key.push_str(authority.as_str());
+        }
+
+        let path = normalized.path().as_str();
+        let drive = path.strip_prefix('/').and_then(|path| {
+            let letter = path.chars().next().filter(char::is_ascii_alphabetic)?;
+            let rest = path[1..]
+                .strip_prefix(':')
+                .or_else(|| path[1..].strip_prefix("%3A"))?;
+            (rest.is_empty() || rest.starts_with('/')).then_some((letter, rest))
+        });
+        match drive {
+            Some((letter, rest)) if normalized.scheme().as_str() == "file" => {
+                key.push('/');
+                key.push(letter.to_ascii_lowercase());
+                key.push(':');
+                key.push_str(rest);
+            }
+            _ => key.push_str(path),
+        }
+
+        if let Some(query) = normalized.query() {
+            key.push('?');
+            key.push_str(query.as_str());
+        }
+        if let Some(fragment) = normalized.fragment() {
+            key.push('#');
+            key.push_str(fragment.as_str());
+        }
+        key
+    }
+
+    /// Returns a copy of this URI whose fragment is `L<line>` or
+    /// `L<line>,<column>`, which some clients use to navigate to a position.
+    ///
+    /// Any existing fragment is replaced. The numbers are written as given:
+    /// clients reading such fragments usually expect them to be one-based.
+    #[must_use]
+    pub fn with_line_fragment(&self, line: u32, column: Option<u32>) -> Self {
+        let fragment =
+            column.map_or_else(|| format!("L{line}"), |column| format!("L{line},{column}"));
+        let mut uri = self.clone();
+        uri.set_fragment(Some(fluent_uri::pct_enc::EStr::new_or_panic(&fragment)));
+        uri
+    }
+
+    /// Returns a copy of this URI whose fragment is `raw`, percent-encoding
+    /// every character not allowed in a fragment, including `%` itself.
+    ///
+    /// Unlike `set_fragment`, which panics on an invalid `EStr`, this accepts
+    /// any string, e.g. JSON or base64 payloads used by notebook and virtual
+    /// schemes. Any existing fragment is replaced. See
+    /// [`Uri::decoded_fragment`] for the reverse operation.
+    #[must_use]
+    pub fn with_encoded_fragment(&self, raw: &str) -> Self {
+        let fragment = percent_encoding::utf8_percent_encode(raw, &FRAGMENT_SET).to_string();
+        let mut uri = self.clone();
+        uri.set_fragment(Some(fluent_uri::pct_enc::EStr::new_or_panic(&fragment)));
+        uri
+    }
+
+    /// The percent-decoded fragment of this URI, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// The inner result is an error if the decoded fragment is not valid UTF-8.
+    #[must_use]
+    pub fn decoded_fragment(&self) -> Option<Result<Cow<'_, str>, std::str::Utf8Error>> {
+        self.fragment()
+            .map(|fragment| percent_encoding::percent_decode_str(fragment.as_str()).decode_utf8())
+    }
+
+    /// Returns a copy of this URI with its path replaced by `path`, which is
+    /// percent-encoded, keeping the scheme, authority, query and fragment.
+    ///
+    /// e.g. replacing the path of `file:///proj/src/main.rs` by
+    /// `/proj/Cargo.toml` gives `file:///proj/Cargo.toml`.
+    ///
+    /// If this URI has an authority, a relative `path` is made absolute. If
+    /// it has none, a `path` starting with `//` gets an empty authority so it
+    /// is not read as one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the resulting URI does not parse, which should not
+    /// happen since `path` is percent-encoded.
+    pub fn with_path(&self, path: &str) -> Result<Self, fluent_uri::ParseError> {
+        let mut uri = format!("{}:", self.scheme().as_str());
+        if let Some(authority) = self.authority() {
+            uri.push_str("//");
+            uri.push_str(authority.as_str());
+            if !path.is_empty() && !path.starts_with('/') {
+                uri.push('/');
+            }
+        } else if path.starts_with("//") {
+            uri.push_str("//");
+        }
+        uri.extend(percent_encoding::utf8_percent_encode(path, &ASCII_SET));
+        if let Some(query) = self.query() {
+            uri.push('?');
+            uri.push_str(query.as_str());
+        }
+        if let Some(fragment) = self.fragment() {
+            uri.push('#');
+            uri.push_str(fragment.as_str());
+        }
+        Self::from_str(&uri)
+    }
+
+    /// Returns a copy of this URI whose path has its `.` and `..` segments
+    /// removed as specified by [RFC 3986 §5.2.4], e.g. `file:///a/./b/../c`
+    /// becomes `file:///a/c`.
+    ///
+    /// A `..` segment backing out of the root is dropped, so `file:///../a`
+    /// becomes `file:///a`. Unlike [`Uri::comparison_key`], nothing else
+    /// about the URI is normalized.
+    ///
+    /// [RFC 3986 §5.2.4]: https://datatracker.ietf.org/doc/html/rfc3986#section-5.2.4
+    #[must_use]
+    pub fn remove_dot_segments(&self) -> Self {
+        let path = self.path().as_str();
+        let mut input = path;
+        let mut output = String::with_capacity(path.len());
+        while !input.is_empty() {
+            if let Some(rest) = input.strip_prefix("../") {
+                input = rest;
+            } else if let Some(rest) = input.strip_prefix("./") {
+                input = rest;
+            } else if input.starts_with("/./") || input == "/." {
+                input = &input[2..];
+                if input.is_empty() {
+                    input = "/";
+                }
+            } else if input.starts_with("/../") || input == "/.." {
+                input = &input[3..];
+                if input.is_empty() {
+                    input = "/";
+                }
+                output.truncate(output.rfind('/').unwrap_or(0));
+            } else if input == "." || input == ".." {
+                input = "";
+            } else {
+                let end = input[1..].find('/').map_or(input.len(), |i| i + 1);
+                output.push_str(&input[..end]);
+                input = &input[end..];
+            }
+        }
+        if output == path {
+            return self.clone();
+        }
+
+        let mut uri = format!("{}:", self.scheme().as_str());
+        if let Some(authority) = self.authority() {
+            uri.push_str("//");
+            uri.push_str(authority.as_str());
+        } else if output.starts_with("//") {
+            uri.push_str("//");
+        }
+        uri.push_str(&output);
+        if let Some(query) = self.query() {
+            uri.push('?');
+            uri.push_str(query.as_str());
+        }
+        if let Some(fragment) = self.fragment() {
+            uri.push('#');
+            uri.push_str(fragment.as_str());
+        }
+        Self::from_str(&uri).unwrap_or_else(|_| self.clone())
+    }
+
+    /// Splits a nested archive URI such as `jar:file:///lib.jar!/pkg/Foo.class`
+    /// into the URI of the archive, `file:///lib.jar`, and the still
+    /// percent-encoded path of the entry inside it, `pkg/Foo.class`.
+    ///
+    /// Returns `None` unless this is a `jar:` or `zip:` URI whose path holds a
+    /// valid URI followed by `!/`.
+    #[must_use]
+    pub fn inner_jar_target(&self) -> Option<(Self, &str)> {
+        let scheme = self.scheme().as_str();
+        if !(scheme.eq_ignore_ascii_case("jar") || scheme.eq_ignore_ascii_case("zip")) {
+            return None;
+        }
+        let (archive, entry) = self.path().as_str().split_once("!/")?;
+        Some((archive.parse().ok()?, entry))
+    }
+
     /// Convert a file path to a [`Uri`].
     ///
     /// Returns `None` if the file does not exist.
+    ///
+    /// On other platforms than Windows, also returns `None` for Windows-style
+    /// paths starting with a drive letter, like `C:\foo` or `C:/foo`, or a
+    /// UNC prefix, like `\\server\share`. Such paths would otherwise be
+    /// resolved relative to the current directory, giving a URI that depends
+    /// on where e.g. tests run.
     pub fn from_file_path<A: AsRef<Path>>(path: A) -> Option<Self> {
         let path = path.as_ref();
 
+        #[cfg(not(windows))]
+        if is_windows_style_path(&path.to_string_lossy()) {
+            return None;
+        }
+
         let fragment = if path.is_absolute() {
             Cow::Borrowed(path)
         } else {
@@ -248,6 +587,132 @@ impl Uri {
     }
 }
 
+/// A [`Uri`] together with its precomputed [`Uri::comparison_key`] and the
+/// hash of that key.
+///
+/// `Uri` keys compare the URIs as spelled, so the same document sent as
+/// `file:///C:/a.rs` and `file:///c%3A/a.rs` ends up twice in a map. Prefer
+/// `CachedUri` as the key of maps holding a whole workspace: the key is
+/// normalized once, and equality checks between different URIs are mostly
+/// settled by comparing the cached hashes.
+///
+/// `Hash` only feeds the cached hash to the hasher. Use
+/// [`BuildCachedUriHasher`] as the map's hasher so that lookups use that hash
+/// as is instead of rehashing it; see `benches/uri_map.rs`.
+#[derive(Debug, Clone)]
+pub struct CachedUri {
+    uri: Uri,
+    key: String,
+    hash: u64,
+}
+
+impl CachedUri {
+    #[must_use]
+    pub fn new(uri: Uri) -> Self {
+        let key = uri.comparison_key();
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        Self {
+            uri,
+            key,
+            hash: hasher.finish(),
+        }
+    }
+
+    /// The URI as it was given.
+    #[must_use]
+    pub const fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// The comparison key of the URI.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The hash of the comparison key, computed once with the standard
+    /// library's default hasher.
+    #[must_use]
+    pub const fn precomputed_hash(&self) -> u64 {
+        self.hash
+    }
+
+    #[must_use]
+    pub fn into_uri(self) -> Uri {
+        self.uri
+    }
+}
+
+impl From<Uri> for CachedUri {
+    fn from(uri: Uri) -> Self {
+        Self::new(uri)
+    }
+}
+
+impl PartialEq for CachedUri {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.key == other.key
+    }
+}
+
+impl Eq for CachedUri {}
+
+impl Hash for CachedUri {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+/// A [`Hasher`] passing the precomputed hash of a [`CachedUri`] through, meant
+/// for maps keyed by `CachedUri`.
+///
+/// The first `u64` written to a fresh hasher is taken as the hash as is, so
+/// plain `u64` keys would hash to themselves. Any other write, including a
+/// further `u64`, is mixed in with FNV-1a.
+#[derive(Debug, Clone, Copy)]
+pub struct CachedUriHasher {
+    hash: u64,
+    fresh: bool,
+}
+
+impl Default for CachedUriHasher {
+    fn default() -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+            fresh: true,
+        }
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+impl Hasher for CachedUriHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.fresh = false;
+        for byte in bytes {
+            self.hash = (self.hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u64(&mut self, hash: u64) {
+        if self.fresh {
+            self.hash = hash;
+            self.fresh = false;
+        } else {
+            self.write(&hash.to_le_bytes());
+        }
+    }
+}
+
+/// Builds [`CachedUriHasher`]s, e.g. `HashMap<CachedUri, V, BuildCachedUriHasher>`.
+pub type BuildCachedUriHasher = BuildHasherDefault<CachedUriHasher>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,6 +721,54 @@ mod tests {
     use std::path::{Path, PathBuf};
     use std::str::FromStr;
 
+    #[test]
+    fn with_line_fragment() {
+        let uri = Uri::from_str("file:///a/b.rs").unwrap();
+        assert_eq!(
+            uri.with_line_fragment(10, None).as_str(),
+            "file:///a/b.rs#L10"
+        );
+        assert_eq!(
+            uri.with_line_fragment(10, Some(5)).as_str(),
+            "file:///a/b.rs#L10,5"
+        );
+
+        let uri = Uri::from_str("https://example.com/a?x=1#old").unwrap();
+        assert_eq!(
+            uri.with_line_fragment(3, None).as_str(),
+            "https://example.com/a?x=1#L3"
+        );
+        assert_eq!(uri.as_str(), "https://example.com/a?x=1#old");
+    }
+
+    #[test]
+    fn encoded_fragment() {
+        let uri = Uri::from_str("vscode-notebook-cell:///a.ipynb#old").unwrap();
+        let with_json = uri.with_encoded_fragment(r#"{"cell":1}"#);
+        assert_eq!(
+            with_json.as_str(),
+            "vscode-notebook-cell:///a.ipynb#%7B%22cell%22:1%7D"
+        );
+        assert_eq!(
+            with_json.decoded_fragment().unwrap().unwrap(),
+            r#"{"cell":1}"#
+        );
+
+        let raw = "a b#c%20d/e?f=+g==";
+        let encoded = uri.with_encoded_fragment(raw);
+        assert_eq!(
+            encoded.fragment().unwrap().as_str(),
+            "a%20b%23c%2520d/e?f=+g=="
+        );
+        assert_eq!(encoded.decoded_fragment().unwrap().unwrap(), raw);
+        assert_eq!(Uri::from_str(encoded.as_str()).unwrap(), encoded);
+
+        let uri = Uri::from_str("file:///a.rs").unwrap();
+        assert!(uri.decoded_fragment().is_none());
+        let invalid = Uri::from_str("file:///a.rs#%FF").unwrap();
+        assert!(invalid.decoded_fragment().unwrap().is_err());
+    }
+
     #[test]
     fn deref_mut_fragment_add() {
         let mut uri = Uri::from_str("https://www.example.com").unwrap();
@@ -268,6 +781,128 @@ mod tests {
         format!("{EXPECTED_SCHEMA}{path}")
     }
 
+    #[test]
+    fn test_with_path() {
+        let uri = Uri::from_str("file:///proj/src/main.rs").unwrap();
+        assert_eq!(
+            uri.with_path("/proj/Cargo.toml").unwrap().as_str(),
+            "file:///proj/Cargo.toml"
+        );
+        assert_eq!(
+            uri.with_path("/proj/my file.rs").unwrap().as_str(),
+            "file:///proj/my%20file.rs"
+        );
+
+        let uri = Uri::from_str("https://user@example.com:8080/a/b?x=1&y=2#top").unwrap();
+        assert_eq!(
+            uri.with_path("/c").unwrap().as_str(),
+            "https://user@example.com:8080/c?x=1&y=2#top"
+        );
+        assert_eq!(
+            uri.with_path("d").unwrap().as_str(),
+            "https://user@example.com:8080/d?x=1&y=2#top"
+        );
+
+        let uri = Uri::from_str("untitled:Untitled-1").unwrap();
+        assert_eq!(
+            uri.with_path("Untitled-2").unwrap().as_str(),
+            "untitled:Untitled-2"
+        );
+        assert_eq!(uri.with_path("//x").unwrap().as_str(), "untitled:////x");
+    }
+
+    #[test]
+    fn test_inner_jar_target() {
+        let uri = Uri::from_str("jar:file:///lib/my%20lib.jar!/pkg/Foo.class").unwrap();
+        let (archive, entry) = uri.inner_jar_target().unwrap();
+        assert_eq!(archive.as_str(), "file:///lib/my%20lib.jar");
+        assert_eq!(entry, "pkg/Foo.class");
+        assert_eq!(uri.to_file_path(), None);
+
+        let uri = Uri::from_str("zip:file:///a.zip!/dir/a.txt").unwrap();
+        assert_eq!(uri.inner_jar_target().unwrap().1, "dir/a.txt");
+
+        for uri in ["jar:file:///lib.jar", "file:///lib.jar!/Foo.class"] {
+            assert_eq!(Uri::from_str(uri).unwrap().inner_jar_target(), None);
+        }
+    }
+
+    #[test]
+    fn test_relative_to() {
+        let base = Uri::from_str("file:///proj").unwrap();
+        let relative = |uri: &str| Uri::from_str(uri).unwrap().relative_to(&base);
+
+        assert_eq!(
+            relative("file:///proj/src/a.rs").as_deref(),
+            Some("src/a.rs")
+        );
+        assert_eq!(
+            Uri::from_str("file:///proj/src/a.rs")
+                .unwrap()
+                .relative_to(&Uri::from_str("file:///proj/").unwrap())
+                .as_deref(),
+            Some("src/a.rs")
+        );
+        assert_eq!(
+            relative("file:///proj/my%20file.rs").as_deref(),
+            Some("my file.rs")
+        );
+
+        assert_eq!(relative("file:///project/a.rs"), None);
+        assert_eq!(relative("file:///other/a.rs"), None);
+        assert_eq!(relative("file:///proj"), None);
+        assert_eq!(relative("untitled:///proj/a.rs"), None);
+        assert_eq!(relative("file://host/proj/a.rs"), None);
+    }
+
+    #[test]
+    fn test_cached_uri() {
+        use std::{collections::HashMap, hash::BuildHasher};
+
+        let uri = |uri: &str| Uri::from_str(uri).unwrap();
+        let a = CachedUri::new(uri("file:///C:/a.rs"));
+        let b = CachedUri::new(uri("file:///c%3A/a.rs"));
+        assert_eq!(a, b);
+        assert_eq!(a.precomputed_hash(), b.precomputed_hash());
+        assert_eq!(b.uri().as_str(), "file:///c%3A/a.rs");
+        assert_ne!(a, CachedUri::new(uri("file:///c:/b.rs")));
+
+        let hasher = BuildCachedUriHasher::default();
+        assert_eq!(hasher.hash_one(&a), a.precomputed_hash());
+        assert_ne!(
+            hasher.hash_one((1_u64, 7_u64)),
+            hasher.hash_one((2_u64, 7_u64))
+        );
+        assert_ne!(hasher.hash_one("a"), hasher.hash_one("b"));
+
+        let mut map = HashMap::<_, _, BuildCachedUriHasher>::default();
+        map.insert(a, 1);
+        assert_eq!(map.get(&b), Some(&1));
+        assert_eq!(map.get(&CachedUri::new(uri("FILE:///C:/a.rs"))), Some(&1));
+        assert_eq!(map.get(&CachedUri::new(uri("file:///c:/b.rs"))), None);
+    }
+
+    #[test]
+    fn test_comparison_key() {
+        let key = |uri: &str| Uri::from_str(uri).unwrap().comparison_key();
+
+        assert_eq!(key("FILE:///a/b.rs"), key("file:///a/b.rs"));
+        assert_eq!(key("HTTP://Example.COM/a"), "http://example.com/a");
+        assert_eq!(key("file:///a/%7e%62.rs"), "file:///a/~b.rs");
+        assert_eq!(key("file:///a%2fb"), "file:///a%2Fb");
+        assert_eq!(key("file:///a/./c/../b.rs"), key("file:///a/b.rs"));
+
+        assert_eq!(key("file:///C:/a.rs"), "file:///c:/a.rs");
+        assert_eq!(key("file:///c%3A/a.rs"), "file:///c:/a.rs");
+        assert_eq!(key("file:///c%3a/a.rs"), "file:///c:/a.rs");
+        assert_eq!(key("file:///C:"), "file:///c:");
+        assert_eq!(key("file:///Cd:/a.rs"), "file:///Cd:/a.rs");
+        assert_eq!(key("untitled:///C:/a.rs"), "untitled:///C:/a.rs");
+
+        assert_ne!(key("file:///a/B.rs"), key("file:///a/b.rs"));
+        assert_eq!(key("file:///a?x#y"), "file:///a?x#y");
+    }
+
     #[test]
     #[cfg(windows)]
     fn test_idempotent_canonicalization() {
@@ -295,6 +930,54 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_non_utf8_path_conversion() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let uri = Uri::from_str("file:///some/path/%FF%FE.txt").unwrap();
+        let expected = Path::new(OsStr::from_bytes(b"/some/path/\xFF\xFE.txt"));
+        assert_eq!(uri.to_file_path_os().unwrap(), expected);
+        assert_ne!(uri.to_file_path().unwrap(), expected);
+
+        assert!(
+            Uri::from_str("file://")
+                .unwrap()
+                .to_file_path_os()
+                .is_none()
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_sub_delims_path_conversion() {
+        // `+` must never be decoded as a space, whether it was encoded or not
+        let uris = [
+            ("file:///a%2Bb.txt", "/a+b.txt"),
+            ("file:///a+b.txt", "/a+b.txt"),
+            ("file:///a%20b.txt", "/a b.txt"),
+            ("file:///a%2Cb%3Bc%3Dd.txt", "/a,b;c=d.txt"),
+            ("file:///a,b;c=d.txt", "/a,b;c=d.txt"),
+            ("file:///%21%24%26%27%28%29%2A.txt", "/!$&'()*.txt"),
+            ("file:///!$&'()*.txt", "/!$&'()*.txt"),
+            ("file:///a%40b%3Ac.txt", "/a@b:c.txt"),
+        ];
+
+        for (uri, expected) in uris {
+            let uri = Uri::from_str(uri).unwrap();
+            let path = uri.to_file_path().unwrap();
+            assert_eq!(path, Path::new(expected), "uri={uri:?}");
+
+            let roundtrip = Uri::from_file_path(&path).unwrap();
+            assert_eq!(roundtrip.to_file_path().unwrap(), path, "uri={roundtrip:?}");
+        }
+
+        assert_eq!(
+            Uri::from_file_path("/a+b.txt").unwrap().as_str(),
+            "file:///a%2Bb.txt"
+        );
+    }
+
     #[test]
     #[cfg(windows)]
     fn test_path_roundtrip_conversion() {
@@ -399,6 +1082,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_remove_dot_segments() {
+        let remove = |uri: &str| Uri::from_str(uri).unwrap().remove_dot_segments();
+
+        assert_eq!(remove("file:///a/./b/../c").as_str(), "file:///a/c");
+        assert_eq!(remove("file:///a/b/c/./../../g").as_str(), "file:///a/g");
+        assert_eq!(remove("file:///a/b/..").as_str(), "file:///a/");
+        assert_eq!(remove("file:///a/.").as_str(), "file:///a/");
+        assert_eq!(remove("file:///../../a").as_str(), "file:///a");
+        assert_eq!(remove("file:///a/../..").as_str(), "file:///");
+        assert_eq!(remove("x:mid/content=5/../6").as_str(), "x:mid/6");
+        assert_eq!(
+            remove("http://h/a/%2E%2E/./b?x=../y#./z").as_str(),
+            "http://h/a/%2E%2E/b?x=../y#./z"
+        );
+        assert_eq!(remove("file:///a/b.rs").as_str(), "file:///a/b.rs");
+        assert_eq!(remove("file:///a..b/.c").as_str(), "file:///a..b/.c");
+    }
+
+    #[test]
+    fn test_is_valid_file_uri() {
+        let valid = |uri: &str| Uri::from_str(uri).unwrap().is_valid_file_uri();
+
+        assert!(!valid("file://"));
+        assert_eq!(valid("file:///"), cfg!(not(windows)));
+        assert!(valid("file:///C:/x"));
+        assert!(valid("FILE:///C:/x"));
+        assert!(!valid("file:x"));
+        assert!(!valid("untitled:///C:/x"));
+    }
+
+    #[test]
+    fn test_refers_to_path() {
+        let refers =
+            |uri: &str, path: &str| Uri::from_str(uri).unwrap().refers_to_path(Path::new(path));
+
+        #[cfg(not(windows))]
+        {
+            assert!(refers("file:///tmp/a%20b.rs", "/tmp/a b.rs"));
+            assert!(refers("FILE:///tmp/./a.rs", "/tmp/a.rs"));
+            assert!(!refers("file:///tmp/A.rs", "/tmp/a.rs"));
+            assert!(!refers("file:///C:/a.rs", "C:\\a.rs"));
+        }
+        #[cfg(windows)]
+        {
+            assert!(refers("file:///c%3A/a%20b.rs", "C:\\a b.rs"));
+            assert!(refers("file:///C:/tmp/A.rs", "c:\\tmp\\a.rs"));
+            assert!(!refers("file:///C:/tmp/a.rs", "D:\\tmp\\a.rs"));
+        }
+
+        assert!(!refers("untitled:a.rs", "a.rs"));
+        assert!(!refers("file:///a.rs", "does/not/exist.rs"));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_from_windows_style_path_on_unix() {
+        assert_eq!(Uri::from_file_path("C:\\foo"), None);
+        assert_eq!(Uri::from_file_path("c:/foo/bar.rs"), None);
+        assert_eq!(Uri::from_file_path("C:"), None);
+        assert_eq!(Uri::from_file_path("\\\\server\\share\\a.rs"), None);
+
+        assert_eq!(
+            Uri::from_file_path("/C:\\foo").unwrap().as_str(),
+            "file:///C%3A%5Cfoo"
+        );
+    }
+
     #[test]
     fn test_invalid_uri_on_windows() {
         let uri = Uri::from_str("file://").unwrap();
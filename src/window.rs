@@ -80,6 +80,17 @@ pub struct MessageActionItem {
     pub properties: HashMap<String, MessageActionItemProperty>,
 }
 
+impl MessageActionItem {
+    /// Creates an action item with the given title and no additional properties.
+    #[must_use]
+    pub fn new(title: String) -> Self {
+        Self {
+            title,
+            properties: HashMap::new(),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum MessageActionItemProperty {
@@ -123,6 +134,36 @@ pub struct ShowMessageRequestParams {
     pub actions: Option<Vec<MessageActionItem>>,
 }
 
+impl ShowMessageRequestParams {
+    /// Creates params offering one action item per title, e.g. `Retry` and `Cancel`.
+    #[must_use]
+    pub fn with_actions(typ: MessageType, message: String, titles: &[&str]) -> Self {
+        Self {
+            typ,
+            message,
+            actions: Some(
+                titles
+                    .iter()
+                    .map(|title| MessageActionItem::new((*title).to_string()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Maps the item returned by the client back to the index of the offered
+    /// action with the same title.
+    ///
+    /// Returns `None` if no action was selected or if it was not offered.
+    #[must_use]
+    pub fn selected_index(&self, selected: Option<&MessageActionItem>) -> Option<usize> {
+        let selected = selected?;
+        self.actions
+            .as_ref()?
+            .iter()
+            .position(|action| action.title == selected.title)
+    }
+}
+
 /// Client capabilities for the show document request.
 #[derive(Debug, PartialEq, Eq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -170,3 +211,31 @@ pub struct ShowDocumentResult {
     /// A boolean indicating if the show was successful.
     pub success: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_serialization;
+
+    #[test]
+    fn test_show_message_request_actions() {
+        let params = ShowMessageRequestParams::with_actions(
+            MessageType::ERROR,
+            "Build failed".to_string(),
+            &["Retry", "Cancel"],
+        );
+        test_serialization(
+            &params,
+            r#"{"type":1,"message":"Build failed","actions":[{"title":"Retry"},{"title":"Cancel"}]}"#,
+        );
+
+        let response: Option<MessageActionItem> =
+            serde_json::from_str(r#"{"title":"Cancel"}"#).unwrap();
+        assert_eq!(params.selected_index(response.as_ref()), Some(1));
+        assert_eq!(
+            params.selected_index(Some(&MessageActionItem::new("Open Log".to_string()))),
+            None
+        );
+        assert_eq!(params.selected_index(None), None);
+    }
+}
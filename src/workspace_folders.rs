@@ -29,6 +29,14 @@ pub struct WorkspaceFolder {
     pub name: String,
 }
 
+impl WorkspaceFolder {
+    /// Whether both folders have the same URI, whatever their display names.
+    #[must_use]
+    pub fn same_uri(&self, other: &WorkspaceFolder) -> bool {
+        self.uri == other.uri
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DidChangeWorkspaceFoldersParams {
@@ -46,3 +54,21 @@ pub struct WorkspaceFoldersChangeEvent {
     /// The array of the removed workspace folders
     pub removed: Vec<WorkspaceFolder>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_folder_same_uri() {
+        let folder = |uri: &str, name: &str| WorkspaceFolder {
+            uri: uri.parse().unwrap(),
+            name: name.to_string(),
+        };
+
+        let a = folder("file:///proj", "proj");
+        assert!(a.same_uri(&folder("file:///proj", "Project")));
+        assert_ne!(a, folder("file:///proj", "Project"));
+        assert!(!a.same_uri(&folder("file:///other", "proj")));
+    }
+}
@@ -49,6 +49,16 @@ pub struct WorkspaceSymbolParams {
     pub query: String,
 }
 
+impl WorkspaceSymbolParams {
+    #[must_use]
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            ..Self::default()
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
 pub struct WorkspaceSymbolResolveSupportCapability {
     /// The properties that a client can resolve lazily. Usually
@@ -92,6 +102,27 @@ pub struct WorkspaceSymbol {
     pub data: Option<LSPAny>,
 }
 
+impl WorkspaceSymbol {
+    #[must_use]
+    pub fn new(name: impl Into<String>, kind: SymbolKind, location: Location) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            tags: None,
+            container_name: None,
+            location: OneOf::Left(location),
+            data: None,
+        }
+    }
+
+    /// Sets the name of the symbol containing this symbol.
+    #[must_use]
+    pub fn with_container(mut self, name: impl Into<String>) -> Self {
+        self.container_name = Some(name.into());
+        self
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 pub struct WorkspaceLocation {
     pub uri: Uri,
@@ -115,3 +146,48 @@ impl From<Vec<WorkspaceSymbol>> for WorkspaceSymbolResponse {
         Self::Nested(symbols)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::{Request, WorkspaceSymbolRequest, WorkspaceSymbolResolve};
+    use crate::tests::test_serialization;
+
+    #[test]
+    fn workspace_symbol_params() {
+        assert_eq!(WorkspaceSymbolRequest::METHOD, "workspace/symbol");
+        assert_eq!(WorkspaceSymbolResolve::METHOD, "workspaceSymbol/resolve");
+
+        test_serialization(&WorkspaceSymbolParams::new("foo"), r#"{"query":"foo"}"#);
+        test_serialization(
+            &WorkspaceSymbolParams {
+                partial_result_params: PartialResultParams::with_token(
+                    crate::NumberOrString::Number(1),
+                ),
+                ..WorkspaceSymbolParams::new("")
+            },
+            r#"{"partialResultToken":1,"query":""}"#,
+        );
+    }
+
+    #[test]
+    fn workspace_symbol_new() {
+        let location = Location::new("file:///a.rs".parse().unwrap(), crate::Range::default());
+        test_serialization(
+            &WorkspaceSymbol::new("Foo", SymbolKind::STRUCT, location),
+            r#"{"name":"Foo","kind":23,"location":{"uri":"file:///a.rs","range":{"start":{"line":0,"character":0},"end":{"line":0,"character":0}}}}"#,
+        );
+
+        let location = Location::new("file:///b.rs".parse().unwrap(), crate::Range::default());
+        let symbol = WorkspaceSymbol {
+            location: OneOf::Right(WorkspaceLocation {
+                uri: "file:///b.rs".parse().unwrap(),
+            }),
+            ..WorkspaceSymbol::new("bar", SymbolKind::FUNCTION, location).with_container("foo")
+        };
+        test_serialization(
+            &symbol,
+            r#"{"name":"bar","kind":12,"containerName":"foo","location":{"uri":"file:///b.rs"}}"#,
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Property-based round-trip tests for file path <-> [`Uri`] conversions.
+//!
+//! These are slower than the unit tests and ignored by default. Run them
+//! with:
+//!
+//! ```sh
+//! cargo test --test uri_proptest -- --ignored
+//! ```
+//!
+//! Set `PROPTEST_CASES` to change the number of generated paths.
+
+use std::path::PathBuf;
+
+use ls_types::Uri;
+use proptest::prelude::*;
+
+/// A single path component: printable characters including spaces, unicode
+/// and URI-reserved characters, excluding separators and characters that are
+/// not allowed in Windows file names.
+fn component() -> impl Strategy<Value = String> {
+    "[^/\\\\:*?\"<>|\\x00-\\x1F]{1,12}"
+        .prop_filter("dot segments are not canonical", |c| c != "." && c != "..")
+        .prop_filter("trailing dots and spaces are stripped on Windows", |c| {
+            !c.ends_with(['.', ' '])
+        })
+}
+
+fn root() -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from("C:\\")
+    } else {
+        PathBuf::from("/")
+    }
+}
+
+proptest! {
+    #[test]
+    #[ignore = "slow, run with `cargo test --test uri_proptest -- --ignored`"]
+    fn file_path_round_trip(components in prop::collection::vec(component(), 1..6)) {
+        let path: PathBuf = std::iter::once(root()).chain(components.iter().map(PathBuf::from)).collect();
+
+        let uri = Uri::from_file_path(&path).expect("absolute paths are always convertible");
+        let round_tripped = uri.to_file_path();
+        prop_assert_eq!(round_tripped.as_deref(), Some(path.as_path()), "uri: {}", uri.as_str());
+
+        let reparsed: Uri = uri.as_str().parse().expect("generated uri must parse");
+        prop_assert_eq!(reparsed, uri);
+    }
+}